@@ -0,0 +1,24 @@
+//! Synthetic dataset shared by the benchmarks.
+
+pub const NUM_ORGS: u32 = 20_000;
+pub const NUM_ASNS: u32 = 100_000;
+
+/// Generate a JSONL dataset of [NUM_ORGS] organizations and [NUM_ASNS] ASes spread evenly
+/// across them.
+pub fn synthetic_jsonl() -> String {
+    let mut content = String::new();
+    for org in 0..NUM_ORGS {
+        content.push_str(&format!(
+            r#"{{"changed":"20240701","name":"Org {org}","country":"US","organizationId":"ORG-{org}-ARIN","source":"ARIN","type":"Organization"}}"#
+        ));
+        content.push('\n');
+    }
+    for asn in 0..NUM_ASNS {
+        let org = asn % NUM_ORGS;
+        content.push_str(&format!(
+            r#"{{"changed":"20240701","asn":"{asn}","name":"AS-{asn}","organizationId":"ORG-{org}-ARIN","opaqueId":"","source":"ARIN","type":"ASN"}}"#
+        ));
+        content.push('\n');
+    }
+    content
+}
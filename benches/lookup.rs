@@ -0,0 +1,96 @@
+//! Micro-benchmark of ASN lookups on a synthetic dataset.
+//!
+//! Run with `cargo bench --bench lookup`.
+
+mod common;
+
+use as2org_rs::As2org;
+use common::NUM_ASNS;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+/// Allocator counting the number of allocations and the number of bytes currently allocated.
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED_BYTES.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+const ROUNDS: u32 = 20;
+
+fn build_dataset() -> As2org {
+    As2org::from_jsonl_str(common::synthetic_jsonl().as_str()).unwrap()
+}
+
+fn main() {
+    let allocated_bytes = ALLOCATED_BYTES.load(Ordering::Relaxed);
+    let as2org = build_dataset();
+    println!(
+        "dataset: {:.1} MiB on the heap",
+        (ALLOCATED_BYTES.load(Ordering::Relaxed) - allocated_bytes) as f64 / (1024.0 * 1024.0)
+    );
+
+    // a realistic mix of origin ASNs: mostly present, some missing
+    let queries: Vec<u32> = (0..NUM_ASNS)
+        .map(|i| (i * 7919) % (NUM_ASNS * 11 / 10))
+        .collect();
+
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        for asn in &queries {
+            black_box(as2org.get_as_info(*asn));
+        }
+    }
+    let elapsed = start.elapsed();
+    println!(
+        "get_as_info: {:.1} ns/lookup, {:.1} allocations/lookup",
+        elapsed.as_nanos() as f64 / (ROUNDS as f64 * queries.len() as f64),
+        (ALLOCATIONS.load(Ordering::Relaxed) - allocations) as f64
+            / (ROUNDS as f64 * queries.len() as f64)
+    );
+
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        for asn in &queries {
+            black_box(as2org.get_as_info_ref(*asn));
+        }
+    }
+    let elapsed = start.elapsed();
+    println!(
+        "get_as_info_ref: {:.1} ns/lookup, {:.1} allocations/lookup",
+        elapsed.as_nanos() as f64 / (ROUNDS as f64 * queries.len() as f64),
+        (ALLOCATIONS.load(Ordering::Relaxed) - allocations) as f64
+            / (ROUNDS as f64 * queries.len() as f64)
+    );
+
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        for asn in &queries {
+            black_box(as2org.are_siblings(*asn, asn + 1));
+        }
+    }
+    let elapsed = start.elapsed();
+    println!(
+        "are_siblings: {:.1} ns/lookup",
+        elapsed.as_nanos() as f64 / (ROUNDS as f64 * queries.len() as f64)
+    );
+}
@@ -0,0 +1,31 @@
+//! Benchmark of parsing a synthetic dataset, to compare builds with and without the `rayon`
+//! feature.
+//!
+//! Run with `cargo bench --bench parse` and `cargo bench --bench parse --features rayon`.
+
+mod common;
+
+use as2org_rs::As2org;
+use std::hint::black_box;
+use std::time::Instant;
+
+const ROUNDS: u32 = 10;
+
+fn main() {
+    let content = common::synthetic_jsonl();
+
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        black_box(As2org::from_jsonl_str(content.as_str()).unwrap());
+    }
+    let elapsed = start.elapsed();
+    println!(
+        "from_jsonl_str ({} lines, rayon {}): {:.1} ms/parse",
+        common::NUM_ORGS + common::NUM_ASNS,
+        match cfg!(feature = "rayon") {
+            true => "enabled",
+            false => "disabled",
+        },
+        elapsed.as_secs_f64() * 1000.0 / ROUNDS as f64
+    );
+}
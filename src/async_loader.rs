@@ -0,0 +1,86 @@
+//! Async constructor for use within tokio-based services, enabled by the `async` feature.
+
+use crate::{
+    parse_as2org_file, parse_as2org_reader, parse_dataset_date, parse_file_listing, As2org,
+    As2orgEntry, As2orgError, ParseOptions, Result, BASE_URL,
+};
+
+impl As2org {
+    /// Create a new `As2org` instance without blocking the async executor.
+    ///
+    /// Remote files (and the discovery of the most recent dataset when no path is given) are
+    /// fetched with `reqwest`, while the CPU-bound parsing runs on tokio's blocking thread pool.
+    /// Must be called from within a tokio runtime.
+    pub async fn new_async(data_file_path: Option<String>) -> Result<Self> {
+        let path = match data_file_path {
+            Some(path) => path,
+            None => get_most_recent_data_async().await?,
+        };
+        let dataset_date = parse_dataset_date(path.as_str());
+
+        let entries = match path.starts_with("http://") || path.starts_with("https://") {
+            true => {
+                let bytes = reqwest::get(path.as_str())
+                    .await?
+                    .error_for_status()?
+                    .bytes()
+                    .await?;
+                spawn_parse(move || {
+                    let reader = std::io::Cursor::new(bytes);
+                    Ok(parse_as2org_reader(path.as_str(), reader, ParseOptions::default())?.0)
+                })
+                .await?
+            }
+            false => {
+                spawn_parse(
+                    move || Ok(parse_as2org_file(path.as_str(), ParseOptions::default())?.0),
+                )
+                .await?
+            }
+        };
+
+        Ok(Self::from_entries(entries, dataset_date))
+    }
+}
+
+/// Get the most recent AS2Org data file from CAIDA without blocking.
+async fn get_most_recent_data_async() -> Result<String> {
+    let content = reqwest::get(BASE_URL)
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    let files = parse_file_listing(content.as_str(), BASE_URL);
+    match files.last() {
+        Some((url, _date)) => Ok(url.clone()),
+        None => Err(As2orgError::NoDatasets(BASE_URL.to_string())),
+    }
+}
+
+/// Run the parsing function on tokio's blocking thread pool.
+async fn spawn_parse<F>(f: F) -> Result<Vec<As2orgEntry>>
+where
+    F: FnOnce() -> Result<Vec<As2orgEntry>> + Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| As2orgError::Io(std::io::Error::other(e)))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::FIXTURE_PATH;
+
+    #[tokio::test]
+    async fn test_new_async_local_file() {
+        let as2org = As2org::new_async(Some(FIXTURE_PATH.to_string()))
+            .await
+            .unwrap();
+        assert_eq!(as2org.get_as_info(15169).unwrap().org_name, "Google LLC");
+        assert_eq!(
+            as2org.dataset_date(),
+            chrono::NaiveDate::from_ymd_opt(2024, 7, 1)
+        );
+    }
+}
@@ -0,0 +1,565 @@
+//! Builder for configuring how an [As2org] instance is loaded.
+
+use crate::{
+    download_to_cache, find_cached_file, find_file_on_date, parse_as2org_reader,
+    parse_dataset_date, parse_file_listing, As2org, As2orgError, ParseError, ParseOptions, Result,
+    BASE_URL,
+};
+use chrono::NaiveDate;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Default delay before the first retry of a failed request.
+const DEFAULT_RETRY_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Builder for [As2org] with options for data source and caching, created with
+/// [As2org::builder] or [As2orgBuilder::new].
+///
+/// ```no_run
+/// use as2org_rs::As2orgBuilder;
+/// use std::time::Duration;
+///
+/// let as2org = As2orgBuilder::new()
+///     .cache_dir("/tmp/as2org-cache")
+///     .prefer_cache_on_network_error(true)
+///     .timeout(Duration::from_secs(60))
+///     .retries(3)
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct As2orgBuilder {
+    data_file_path: Option<String>,
+    date: Option<NaiveDate>,
+    cache_dir: Option<String>,
+    prefer_cache_on_network_error: bool,
+    fix_encoding: bool,
+    lenient: bool,
+    timeout: Option<Duration>,
+    retries: u32,
+    retry_backoff: Duration,
+    headers: Vec<(String, String)>,
+    base_url: String,
+}
+
+/// Report of issues encountered while building an [As2org] instance, see
+/// [As2orgBuilder::build_with_report].
+#[derive(Debug, Default)]
+pub struct BuildReport {
+    /// lines that failed to parse and were skipped in [lenient](As2orgBuilder::lenient) mode
+    pub skipped_lines: Vec<ParseError>,
+    /// error that caused a cached file to be loaded instead of the requested dataset, see
+    /// [As2orgBuilder::prefer_cache_on_network_error]
+    pub network_error: Option<As2orgError>,
+}
+
+impl Default for As2orgBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl As2orgBuilder {
+    /// Create a builder that loads the most recent dataset from CAIDA.
+    pub fn new() -> Self {
+        Self {
+            data_file_path: None,
+            date: None,
+            cache_dir: None,
+            prefer_cache_on_network_error: false,
+            fix_encoding: true,
+            lenient: false,
+            timeout: None,
+            retries: 0,
+            retry_backoff: DEFAULT_RETRY_BACKOFF,
+            headers: vec![],
+            base_url: BASE_URL.to_string(),
+        }
+    }
+
+    /// Load the given local or remote data file instead of the most recent dataset.
+    pub fn source(mut self, path: &str) -> Self {
+        self.data_file_path = Some(path.to_string());
+        self
+    }
+
+    /// Load the dataset published on the given date instead of the most recent one.
+    ///
+    /// Building fails with [As2orgError::DateNotFound] if no dataset was published on that date.
+    /// Has no effect if a [data file](Self::source) is set.
+    pub fn date(mut self, date: NaiveDate) -> Self {
+        self.date = Some(date);
+        self
+    }
+
+    /// Discover datasets at the given base URL, e.g. an internal mirror of CAIDA's dataset
+    /// directory, instead of CAIDA's public one.
+    pub fn mirror(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.to_string();
+        self
+    }
+
+    /// Download the dataset into `cache_dir` before loading it.
+    ///
+    /// Downloaded files keep their `YYYYMMDD.as-org2info.jsonl.gz` file name.
+    pub fn cache_dir(mut self, cache_dir: &str) -> Self {
+        self.cache_dir = Some(cache_dir.to_string());
+        self
+    }
+
+    /// Load the newest file in the cache directory if discovering or downloading the most recent
+    /// dataset fails instead of returning an error.
+    ///
+    /// The error is reported by [build_with_report](Self::build_with_report), and additionally
+    /// logged as a warning with the `logging` feature.
+    ///
+    /// Disabled by default to avoid silently serving stale data. Has no effect without a
+    /// [cache directory](Self::cache_dir). With a [date](Self::date), only a cached file of that
+    /// date is used.
+    pub fn prefer_cache_on_network_error(mut self, prefer_cache: bool) -> Self {
+        self.prefer_cache_on_network_error = prefer_cache;
+        self
+    }
+
+    /// Set whether mis-encoded AS and organization names are repaired with
+    /// [fix_latin1_misinterpretation](crate::fix_latin1_misinterpretation). Enabled by default.
+    ///
+    /// When disabled, the names are kept exactly as they appear in the data file.
+    pub fn fix_encoding(mut self, fix_encoding: bool) -> Self {
+        self.fix_encoding = fix_encoding;
+        self
+    }
+
+    /// Set whether lines that fail to parse are skipped instead of aborting the loading. Disabled
+    /// by default.
+    ///
+    /// The skipped lines are reported by [build_with_report](Self::build_with_report).
+    pub fn lenient(mut self, lenient: bool) -> Self {
+        self.lenient = lenient;
+        self
+    }
+
+    /// Set the timeout of each HTTP request for discovering and downloading the dataset.
+    ///
+    /// Setting a timeout, [retries](Self::retries) or [headers](Self::header) makes the builder
+    /// send its own HTTP requests instead of going through `oneio`.
+    ///
+    /// By default, requests go through `oneio` with its own timeout behaviour, as with
+    /// [As2org::new]. When only retries or headers are set, the builder's own HTTP client applies
+    /// its default timeout of 30 seconds.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Set how many times a failed HTTP request is retried. Defaults to no retries.
+    ///
+    /// Only transient failures are retried, i.e. connection errors, timeouts and server errors.
+    /// Other errors such as `404 Not Found` are returned immediately.
+    ///
+    /// Only failures before the response starts arriving are retried, as the data file is parsed
+    /// while it is being downloaded.
+    pub fn retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Set the delay before the first retry, which doubles for every further retry. Defaults to
+    /// one second.
+    pub fn retry_backoff(mut self, backoff: Duration) -> Self {
+        self.retry_backoff = backoff;
+        self
+    }
+
+    /// Add a header to each HTTP request, e.g. an `Authorization` header for an authenticated
+    /// proxy or mirror.
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Load the dataset with the configured options.
+    pub fn build(self) -> Result<As2org> {
+        let (as2org, _report) = self.build_with_report()?;
+        Ok(as2org)
+    }
+
+    /// Load the dataset with the configured options, also returning a [BuildReport] of the
+    /// issues encountered, e.g. the lines skipped in [lenient](Self::lenient) mode.
+    pub fn build_with_report(self) -> Result<(As2org, BuildReport)> {
+        if let Some(path) = self.data_file_path.as_deref() {
+            if !is_http(path) {
+                return self.load_file(path);
+            }
+        }
+        let Some(cache_dir) = self.cache_dir.as_deref() else {
+            let url = self.resolve_url()?;
+            if !self.needs_http_client() {
+                return self.load_file(url.as_str());
+            }
+            let response = self.fetch(url.as_str())?;
+            let (entries, skipped_lines) =
+                parse_as2org_reader(url.as_str(), response, self.parse_options())?;
+            let as2org = As2org::from_entries(entries, parse_dataset_date(url.as_str()));
+            let report = BuildReport {
+                skipped_lines,
+                network_error: None,
+            };
+            return Ok((as2org, report));
+        };
+
+        std::fs::create_dir_all(cache_dir)?;
+        match self.download_into(cache_dir) {
+            Ok(path) => self.load_file(path_to_string(&path).as_str()),
+            Err(e) => match find_cached_file(cache_dir) {
+                Some(path) if self.prefer_cache_on_network_error && self.matches_date(&path) => {
+                    #[cfg(feature = "logging")]
+                    log::warn!(
+                        "failed to fetch the latest AS2Org dataset ({e}), using cached file {}",
+                        path.display()
+                    );
+                    let (as2org, mut report) = self.load_file(path_to_string(&path).as_str())?;
+                    report.network_error = Some(e);
+                    Ok((as2org, report))
+                }
+                _ => Err(e),
+            },
+        }
+    }
+}
+
+impl As2orgBuilder {
+    fn parse_options(&self) -> ParseOptions {
+        ParseOptions {
+            lenient: self.lenient,
+            fix_encoding: self.fix_encoding,
+        }
+    }
+
+    /// Check whether the cached file at the given path is of the configured date, if any.
+    fn matches_date(&self, path: &Path) -> bool {
+        match self.date {
+            Some(date) => parse_dataset_date(path.to_string_lossy().as_ref()) == Some(date),
+            None => true,
+        }
+    }
+
+    /// Check whether the HTTP requests need options that `oneio` does not support.
+    fn needs_http_client(&self) -> bool {
+        self.timeout.is_some() || self.retries > 0 || !self.headers.is_empty()
+    }
+
+    /// Load a local or remote data file through `oneio`.
+    fn load_file(&self, path: &str) -> Result<(As2org, BuildReport)> {
+        let (as2org, skipped_lines) = As2org::load(Some(path.to_string()), self.parse_options())?;
+        let report = BuildReport {
+            skipped_lines,
+            network_error: None,
+        };
+        Ok((as2org, report))
+    }
+
+    /// Get the URL of the configured data file or discover the most recent dataset.
+    fn resolve_url(&self) -> Result<String> {
+        if let Some(path) = &self.data_file_path {
+            return Ok(path.clone());
+        }
+        let base_url = match self.base_url.ends_with('/') {
+            true => self.base_url.clone(),
+            false => format!("{}/", self.base_url),
+        };
+        let files = match self.needs_http_client() {
+            true => parse_file_listing(self.fetch(base_url.as_str())?.text()?.as_str(), &base_url),
+            false => As2org::get_all_files_with_dates_from(base_url.as_str())?,
+        };
+        if let Some(date) = self.date {
+            let (url, _) = find_file_on_date(&files, date)?;
+            return Ok(url.clone());
+        }
+        match files.last() {
+            Some((url, _date)) => Ok(url.clone()),
+            None => Err(As2orgError::NoDatasets(base_url)),
+        }
+    }
+
+    /// Download the data file into the cache directory, returning the path of the cached file.
+    fn download_into(&self, cache_dir: &str) -> Result<PathBuf> {
+        let url = self.resolve_url()?;
+        download_to_cache(cache_dir, url.as_str(), |url, tmp_path| {
+            match self.needs_http_client() {
+                true => {
+                    let mut response = self.fetch(url)?;
+                    let mut file = std::fs::File::create(tmp_path)?;
+                    std::io::copy(&mut response, &mut file)?;
+                }
+                false => oneio::download(url, tmp_path.to_string_lossy().as_ref(), None)?,
+            }
+            Ok(())
+        })
+    }
+
+    /// Request the content at the given URL, retrying failed requests with exponential backoff.
+    fn fetch(&self, url: &str) -> Result<reqwest::blocking::Response> {
+        let mut builder = reqwest::blocking::Client::builder();
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        let client = builder.build()?;
+
+        let mut backoff = self.retry_backoff;
+        let mut attempt = 0;
+        loop {
+            let mut request = client.get(url);
+            for (name, value) in &self.headers {
+                request = request.header(name.as_str(), value.as_str());
+            }
+            match request.send().and_then(|res| res.error_for_status()) {
+                Ok(res) => return Ok(res),
+                Err(e) if is_transient(&e) && attempt < self.retries => {
+                    std::thread::sleep(backoff);
+                    backoff *= 2;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+}
+
+/// Check whether a failed request is worth retrying, i.e. whether it failed to connect, timed out
+/// or got a server error.
+fn is_transient(e: &reqwest::Error) -> bool {
+    e.is_connect() || e.is_timeout() || e.status().is_some_and(|s| s.is_server_error())
+}
+
+fn is_http(path: &str) -> bool {
+    path.starts_with("http://") || path.starts_with("https://")
+}
+
+fn path_to_string(path: &Path) -> String {
+    path.to_string_lossy().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::TempDir;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_prefer_cache_on_network_error() {
+        let dir = TempDir::new("builder");
+        dir.write(
+            "20240101.as-org2info.jsonl.zst",
+            zstd::encode_all(DATA.as_bytes(), 3).unwrap(),
+        );
+
+        // an unreachable mirror makes the download fail
+        let builder = As2orgBuilder {
+            base_url: "http://127.0.0.1:1/".to_string(),
+            ..As2orgBuilder::new().cache_dir(dir.path())
+        };
+        assert!(builder.clone().build().is_err());
+
+        let (as2org, report) = builder
+            .prefer_cache_on_network_error(true)
+            .build_with_report()
+            .unwrap();
+        assert_eq!(as2org.dataset_date(), NaiveDate::from_ymd_opt(2024, 1, 1));
+        assert!(as2org.contains_asn(15169));
+        assert!(report.network_error.is_some());
+    }
+
+    #[test]
+    fn test_failed_download_leaves_no_file() {
+        let dir = TempDir::new("builder-failed");
+        let url = serve(vec![(404, vec![])]);
+        let res = As2orgBuilder::new()
+            .source(&url)
+            .cache_dir(dir.path())
+            .retries(1)
+            .build();
+        assert!(res.is_err());
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+
+    /// Serve the given status codes and bodies to consecutive connections.
+    fn serve(responses: Vec<(u16, Vec<u8>)>) -> String {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut buf = [0u8; 4096];
+                let _ = stream.read(&mut buf);
+                let header = format!(
+                    "HTTP/1.1 {status} Status\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    body.len()
+                );
+                stream.write_all(header.as_bytes()).unwrap();
+                stream.write_all(&body).unwrap();
+            }
+        });
+        format!("http://{addr}/20240101.as-org2info.jsonl")
+    }
+
+    const DATA: &str = r#"{"organizationId":"GOGL-ARIN","changed":"20231115","name":"Google LLC","country":"US","source":"ARIN","type":"Organization"}
+{"asn":"15169","changed":"20231115","name":"GOOGLE","opaqueId":"x","organizationId":"GOGL-ARIN","source":"ARIN","type":"ASN"}
+"#;
+
+    #[test]
+    fn test_fix_encoding() {
+        let dir = TempDir::new("encoding");
+        let path = dir.write(
+            "20240101.as-org2info.jsonl",
+            DATA.replace("Google LLC", "OrangÃ©"),
+        );
+        let path = path.as_str();
+
+        let as2org = As2orgBuilder::new().source(path).build().unwrap();
+        assert_eq!(as2org.get_org_info("GOGL-ARIN").unwrap().name, "Orangé");
+
+        let as2org = As2orgBuilder::new()
+            .source(path)
+            .fix_encoding(false)
+            .build()
+            .unwrap();
+        assert_eq!(as2org.get_org_info("GOGL-ARIN").unwrap().name, "OrangÃ©");
+    }
+
+    #[test]
+    fn test_retries() {
+        let url = serve(vec![(503, vec![]), (200, DATA.into())]);
+        assert!(As2orgBuilder::new().source(&url).build().is_err());
+
+        let url = serve(vec![(503, vec![]), (200, DATA.into())]);
+        let as2org = As2orgBuilder::new()
+            .source(&url)
+            .retries(1)
+            .retry_backoff(Duration::from_millis(10))
+            .build()
+            .unwrap();
+        assert_eq!(as2org.dataset_date(), NaiveDate::from_ymd_opt(2024, 1, 1));
+        assert!(as2org.contains_asn(15169));
+
+        // client errors are not retried
+        let url = serve(vec![(404, vec![]), (200, DATA.into())]);
+        let res = As2orgBuilder::new()
+            .source(&url)
+            .retries(3)
+            .retry_backoff(Duration::from_millis(10))
+            .build();
+        match res {
+            Err(As2orgError::Http(e)) => assert_eq!(e.status().map(|s| s.as_u16()), Some(404)),
+            res => panic!("expected a 404 error, got {:?}", res.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn test_timeout() {
+        // accept connections without ever responding
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!(
+            "http://{}/20240101.as-org2info.jsonl",
+            listener.local_addr().unwrap()
+        );
+
+        let start = std::time::Instant::now();
+        let res = As2orgBuilder::new()
+            .source(&url)
+            .timeout(Duration::from_millis(200))
+            .build();
+        assert!(matches!(res, Err(As2orgError::Http(_))));
+        assert!(start.elapsed() < Duration::from_secs(10));
+        drop(listener);
+    }
+
+    #[test]
+    fn test_date_and_mirror() {
+        use flate2::{write::GzEncoder, Compression};
+        use std::io::Write;
+
+        let listing = r#"<a href="20240101.as-org2info.jsonl.gz">20240101.as-org2info.jsonl.gz</a>
+<a href="20240401.as-org2info.jsonl.gz">20240401.as-org2info.jsonl.gz</a>"#;
+        let mut encoder = GzEncoder::new(vec![], Compression::default());
+        encoder.write_all(DATA.as_bytes()).unwrap();
+        let data = encoder.finish().unwrap();
+
+        let url = serve(vec![(200, listing.into()), (200, data)]);
+        let mirror = url.trim_end_matches("20240101.as-org2info.jsonl");
+        let as2org = As2org::builder()
+            .mirror(mirror)
+            .date(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap())
+            .build()
+            .unwrap();
+        assert_eq!(as2org.dataset_date(), NaiveDate::from_ymd_opt(2024, 1, 1));
+        assert!(as2org.contains_asn(15169));
+
+        let url = serve(vec![(200, listing.into())]);
+        let mirror = url.trim_end_matches("20240101.as-org2info.jsonl");
+        let res = As2org::builder()
+            .mirror(mirror)
+            .date(NaiveDate::from_ymd_opt(2024, 2, 1).unwrap())
+            .build();
+        match res {
+            Err(As2orgError::DateNotFound { nearby, .. }) => assert_eq!(
+                nearby,
+                vec![
+                    NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+                    NaiveDate::from_ymd_opt(2024, 4, 1).unwrap()
+                ]
+            ),
+            _ => panic!("expected DateNotFound"),
+        }
+    }
+
+    #[test]
+    fn test_lenient() {
+        let dir = TempDir::new("lenient");
+        let path = dir.write("20240101.as-org2info.jsonl", format!("{DATA}not json\n"));
+        let path = path.as_str();
+
+        assert!(As2org::builder().source(path).build().is_err());
+        let (as2org, report) = As2org::builder()
+            .source(path)
+            .lenient(true)
+            .build_with_report()
+            .unwrap();
+        assert!(as2org.contains_asn(15169));
+        let lines: Vec<usize> = report.skipped_lines.iter().map(|e| e.line).collect();
+        assert_eq!(lines, vec![3]);
+    }
+
+    #[test]
+    fn test_headers() {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!(
+            "http://{}/20240101.as-org2info.jsonl",
+            listener.local_addr().unwrap()
+        );
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 4096];
+            let len = stream.read(&mut buf).unwrap();
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                DATA.len(),
+                DATA
+            );
+            stream.write_all(response.as_bytes()).unwrap();
+            String::from_utf8_lossy(&buf[..len]).to_lowercase()
+        });
+
+        let headers = vec![("Authorization".to_string(), "Bearer token".to_string())];
+        let as2org = As2org::new_from_url_with_headers(&url, &headers).unwrap();
+        assert!(as2org.contains_asn(15169));
+        assert!(server
+            .join()
+            .unwrap()
+            .contains("authorization: bearer token\r\n"));
+    }
+}
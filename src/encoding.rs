@@ -0,0 +1,106 @@
+//! Repair of names that were UTF-8 encoded, misread as Latin-1/Windows-1252 and encoded again.
+
+use std::borrow::Cow;
+
+/// Maximum number of nested misinterpretations to undo, e.g. `Ã¢â‚¬â„¢` for `’` takes two.
+const MAX_ROUNDS: usize = 3;
+
+/// Fix text where UTF-8 bytes were misinterpreted as Latin-1/Windows-1252 characters, such as
+/// `OrangÃ©` for `Orangé` or `â€™` for `’`.
+///
+/// The whole string is re-decoded: every character is mapped back to its Windows-1252 byte, and
+/// the resulting bytes are decoded as UTF-8. This is repeated to undo multiple rounds of
+/// misinterpretation. Strings that cannot be mapped back or do not form valid UTF-8, such as
+/// correctly encoded `Société`, are returned unchanged.
+///
+/// A non-breaking space (byte `0xA0`) following `Ã` is often replaced by a regular space, as in
+/// `SocietÃ ` for `Società`. Such a space is treated as `0xA0` when `Ã` follows a lowercase ASCII
+/// letter.
+pub fn fix_latin1_misinterpretation(text: &str) -> Cow<'_, str> {
+    if text.is_ascii() {
+        return Cow::Borrowed(text);
+    }
+
+    let mut fixed: Option<String> = None;
+    for _ in 0..MAX_ROUNDS {
+        let current = fixed.as_deref().unwrap_or(text);
+        match redecode(current).or_else(|| redecode(&restore_nbsp(current)?)) {
+            Some(redecoded) => fixed = Some(redecoded),
+            None => break,
+        }
+    }
+    match fixed {
+        Some(fixed) => Cow::Owned(fixed),
+        None => Cow::Borrowed(text),
+    }
+}
+
+/// Map the characters of the text back to Windows-1252 bytes and decode them as UTF-8.
+///
+/// Returns `None` if the text does not change.
+fn redecode(text: &str) -> Option<String> {
+    let bytes = text
+        .chars()
+        .map(windows1252_byte)
+        .collect::<Option<Vec<u8>>>()?;
+    let decoded = String::from_utf8(bytes).ok()?;
+    (decoded != text).then_some(decoded)
+}
+
+/// Replace spaces following `Ã` after a lowercase ASCII letter with non-breaking spaces.
+///
+/// Returns `None` if there is no such space.
+fn restore_nbsp(text: &str) -> Option<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut restored = false;
+    let res = chars
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            if *c == ' ' && i >= 2 && chars[i - 1] == 'Ã' && chars[i - 2].is_ascii_lowercase() {
+                restored = true;
+                '\u{a0}'
+            } else {
+                *c
+            }
+        })
+        .collect();
+    restored.then_some(res)
+}
+
+/// Get the Windows-1252 byte of a character, treating the bytes undefined in Windows-1252 as
+/// Latin-1 control characters.
+fn windows1252_byte(c: char) -> Option<u8> {
+    let byte = match c {
+        '\u{0}'..='\u{ff}' => c as u8,
+        '€' => 0x80,
+        '‚' => 0x82,
+        'ƒ' => 0x83,
+        '„' => 0x84,
+        '…' => 0x85,
+        '†' => 0x86,
+        '‡' => 0x87,
+        'ˆ' => 0x88,
+        '‰' => 0x89,
+        'Š' => 0x8A,
+        '‹' => 0x8B,
+        'Œ' => 0x8C,
+        'Ž' => 0x8E,
+        '‘' => 0x91,
+        '’' => 0x92,
+        '“' => 0x93,
+        '”' => 0x94,
+        '•' => 0x95,
+        '–' => 0x96,
+        '—' => 0x97,
+        '˜' => 0x98,
+        '™' => 0x99,
+        'š' => 0x9A,
+        '›' => 0x9B,
+        'œ' => 0x9C,
+        'ž' => 0x9E,
+        'Ÿ' => 0x9F,
+        _ => return None,
+    };
+    Some(byte)
+}
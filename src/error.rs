@@ -0,0 +1,69 @@
+//! Error types returned by `as2org-rs`.
+
+use chrono::NaiveDate;
+use thiserror::Error;
+
+/// Errors that can occur when loading an AS2Org dataset.
+#[derive(Debug, Error)]
+pub enum As2orgError {
+    /// Failed to retrieve a remote or local resource.
+    #[cfg(feature = "oneio")]
+    #[error("network error: {0}")]
+    Network(#[from] oneio::OneIoError),
+
+    /// Failed to read the input data.
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// A line of the input data could not be parsed.
+    #[error("failed to parse line {line}: {source}")]
+    Parse {
+        /// 1-based line number of the line in the input data
+        line: usize,
+        #[source]
+        source: serde_json::Error,
+    },
+
+    /// Failed to serialize or deserialize JSON data.
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// A cache file written by [crate::As2org::save_cache] is invalid or uses an unsupported
+    /// format version.
+    #[error("invalid cache file: {0}")]
+    InvalidCache(String),
+
+    /// Failed to fetch a remote resource over HTTP.
+    #[cfg(feature = "oneio")]
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+
+    /// The data file at the given location was parsed without producing any AS or organization
+    /// records, e.g. because it is empty or all of its lines were skipped in lenient mode.
+    #[error("{0} did not contain any as-org2info records")]
+    NoRecords(String),
+
+    /// No as-org2info datasets were found at the given location.
+    #[error("no as-org2info files found at {0}")]
+    NoDatasets(String),
+
+    /// No dataset was published on the requested date.
+    #[error("no as2org dataset found for {date}, nearby available dates: {nearby:?}")]
+    DateNotFound {
+        date: NaiveDate,
+        /// the closest available dataset dates before and after the requested date
+        nearby: Vec<NaiveDate>,
+    },
+}
+
+/// Reasons why looking up an AS with [crate::As2org::lookup] failed.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum LookupError {
+    /// The ASN is not in the dataset.
+    #[error("AS{0} not found in the dataset")]
+    UnknownAsn(u32),
+
+    /// The AS is in the dataset, but its organization is missing.
+    #[error("organization {org_id} of AS{asn} not found in the dataset")]
+    MissingOrg { asn: u32, org_id: String },
+}
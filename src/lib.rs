@@ -3,33 +3,106 @@
 //! ## Data source
 //! * The CAIDA [AS Organizations Dataset](http://www.caida.org/data/as-organizations).
 //!
+//! Data files in the JSONL format as well as the older JSON array format are supported.
+//!
 //! ## Data structure
 //!
 //! `As2orgAsInfo`:
 //! * `asn`: the AS number
 //! * `name`: the name provide for the individual AS number
-//! * `country_code`: the country code of the organization's registration country
+//! * `country_code`: the country code of the organization's registration country, normalized to
+//!   trimmed uppercase
 //! * `org_id`: maps to an organization entry
 //! * `org_name`: the name of the organization
 //! * `source`: the RIR or NIR database which was contained this entry
+//! * `data_type`: the type of the AS record
+//! * `opaque_id`: opaque identifier used by RIR extended delegation format
+//! * `as_changed`: the date the AS record was last changed, if available
+//! * `org_changed`: the date the organization record was last changed, if available
+//!
+//! `As2orgOrgInfo`:
+//! * `org_id`: the unique ID of the organization
+//! * `name`: the name of the organization
+//! * `country`: the country code of the organization's registration country
+//! * `source`: the RIR or NIR database which was contained this entry
+//! * `data_type`: the type of the record
+//! * `changed`: the date the record was last changed, if available
 //!
 //! ## Examples
 //!
-//! ```rust
+//! ```no_run
+//! # #[cfg(feature = "oneio")]
+//! # {
 //! use as2org_rs::As2org;
 //!
 //! let as2org = As2org::new(None).unwrap();
 //! dbg!(as2org.get_as_info(400644).unwrap());
 //! dbg!(as2org.get_siblings(15169).unwrap());
+//! dbg!(as2org.get_org_info("GOGL-ARIN").unwrap());
 //! assert!(as2org.are_siblings(15169, 36040));
+//! # }
 //! ```
+//!
+//! ## Optional features
+//!
+//! * `oneio` (enabled by default): loads datasets from local files and URLs, including
+//!   `As2org::new`, the cache and the builder. Disabling default features leaves the in-memory
+//!   constructors such as `As2org::from_jsonl_str`, which allows building for targets without
+//!   filesystem or network access such as `wasm32-unknown-unknown`
+//! * `async`: adds `As2org::new_async` to load datasets within a tokio runtime without blocking
+//!   the executor
+//! * `rayon`: deserializes the input lines in parallel across the available cores, at the cost of
+//!   holding all raw lines in memory during parsing. On a single core this is slower than
+//!   sequential parsing; compare both with `cargo bench --bench parse [--features rayon]` on the
+//!   target machine
+//! * `logging`: logs warnings through the `log` crate, such as the lines skipped in lenient mode
+//!   and falling back to a cached dataset. The library never prints to stdout or stderr
+
+#[cfg(feature = "async")]
+mod async_loader;
+#[cfg(feature = "oneio")]
+mod builder;
+mod country;
+mod encoding;
+mod error;
 
-use anyhow::{anyhow, Result};
+#[cfg(feature = "oneio")]
+pub use builder::{As2orgBuilder, BuildReport};
+pub use encoding::fix_latin1_misinterpretation;
+pub use error::{As2orgError, LookupError};
+
+use chrono::NaiveDate;
+use flate2::read::GzDecoder;
 use regex::Regex;
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::borrow::Cow;
+#[cfg(feature = "oneio")]
+use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap};
+#[cfg(feature = "oneio")]
+use std::io::Read;
+use std::io::{BufRead, BufReader, Write};
+#[cfg(feature = "oneio")]
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock};
+#[cfg(feature = "oneio")]
+use std::time::{Duration, SystemTime};
+
+type Result<T> = std::result::Result<T, As2orgError>;
 
-/// Organization JSON format
+const BASE_URL: &str = "https://publicdata.caida.org/datasets/as-organizations/";
+
+/// Matches the dataset date in an as-org2info file name.
+static DATASET_DATE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(\d{8})\.as-org2info\.").unwrap());
+
+/// Matches the data file names linked from a dataset index page.
+#[cfg(feature = "oneio")]
+static DATA_LINK_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(\d{8}\.as-org2info\.jsonl\.(?:gz|zst))").unwrap());
+
+/// Raw organization record of the dataset, in CAIDA's JSON format
 ///
 /// --------------------
 /// Organization fields
@@ -45,26 +118,39 @@ use std::collections::HashMap;
 /// country : some WHOIS provide as a individual field. In other cases
 ///            we inferred it from the addresses
 /// source  : the RIR or NIR database which was contained this entry
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct As2orgJsonOrg {
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OrgRecord {
     #[serde(alias = "organizationId")]
-    org_id: String,
+    pub(crate) org_id: Arc<str>,
 
-    changed: Option<String>,
+    pub changed: Option<String>,
 
     #[serde(default)]
-    name: String,
+    pub name: String,
+
+    pub(crate) country: Arc<str>,
+
+    pub(crate) source: Arc<str>,
+}
 
-    country: String,
+impl OrgRecord {
+    /// Get the ID of the organization.
+    pub fn org_id(&self) -> &str {
+        &self.org_id
+    }
 
-    /// The RIR or NIR database that contained this entry
-    source: String,
+    /// Get the country code of the organization.
+    pub fn country(&self) -> &str {
+        &self.country
+    }
 
-    #[serde(alias = "type")]
-    data_type: String,
+    /// Get the RIR or NIR database that contained this entry.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
 }
 
-/// AS Json format
+/// Raw AS record of the dataset, in CAIDA's JSON format
 ///
 /// ----------
 /// AS fields
@@ -75,35 +161,154 @@ struct As2orgJsonOrg {
 /// org_id  : maps to an organization entry
 /// opaque_id   : opaque identifier used by RIR extended delegation format
 /// source  : the RIR or NIR database which was contained this entry
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct As2orgJsonAs {
-    asn: String,
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AsRecord {
+    #[serde(deserialize_with = "deserialize_asn")]
+    pub asn: u32,
 
-    changed: Option<String>,
+    pub changed: Option<String>,
 
     #[serde(default)]
-    name: String,
+    pub name: String,
 
     #[serde(alias = "opaqueId")]
-    opaque_id: Option<String>,
+    pub opaque_id: Option<String>,
 
     #[serde(alias = "organizationId")]
-    org_id: String,
+    pub(crate) org_id: Arc<str>,
+
+    pub(crate) source: Arc<str>,
+}
 
-    /// The RIR or NIR database that contained this entry
-    source: String,
+impl AsRecord {
+    /// Get the ID of the organization the AS belongs to.
+    pub fn org_id(&self) -> &str {
+        &self.org_id
+    }
 
-    #[serde(rename = "type")]
-    data_type: String,
+    /// Get the RIR or NIR database that contained this entry.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-enum As2orgJsonEntry {
-    Org(As2orgJsonOrg),
-    As(As2orgJsonAs),
+/// Version of the cache file format written by [As2org::save_cache]
+#[cfg(feature = "oneio")]
+const CACHE_FORMAT_VERSION: u32 = 1;
+
+/// First line of a cache file, identifying its format version
+#[cfg(feature = "oneio")]
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheHeader {
+    version: u32,
+}
+
+/// Cached dataset content, written after the [CacheHeader] line
+#[cfg(feature = "oneio")]
+#[derive(Debug, Serialize)]
+struct CacheDataRef<'a> {
+    dataset_date: Option<NaiveDate>,
+    as_entries: Vec<&'a AsRecord>,
+    org_entries: Vec<&'a OrgRecord>,
+}
+
+/// Owned version of [CacheDataRef] used when loading a cache file
+#[cfg(feature = "oneio")]
+#[derive(Debug, Deserialize)]
+struct CacheData {
+    dataset_date: Option<NaiveDate>,
+    as_entries: Vec<AsRecord>,
+    org_entries: Vec<OrgRecord>,
 }
 
+/// Deserialize an ASN given as a JSON string (or number) into a `u32`.
+///
+/// Strings may use asplain (`65546`) or asdot (`1.10`) notation.
+fn deserialize_asn<'de, D>(deserializer: D) -> std::result::Result<u32, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum AsnValue {
+        Number(u32),
+        String(String),
+    }
+
+    match AsnValue::deserialize(deserializer)? {
+        AsnValue::Number(asn) => Ok(asn),
+        AsnValue::String(asn) => parse_asplain_or_asdot(asn.as_str())
+            .ok_or_else(|| serde::de::Error::custom(format!("invalid ASN {asn:?}"))),
+    }
+}
+
+/// Parse an ASN from user input such as `AS15169`, `asn 15169` or `15169`.
+///
+/// Surrounding whitespace and a case-insensitive `AS` or `ASN` prefix are ignored. The number may
+/// use asplain (`65546`) or asdot (`1.10`) notation. Returns `None` if the input is not a valid
+/// 32-bit ASN.
+///
+/// ```rust
+/// use as2org_rs::parse_asn;
+///
+/// assert_eq!(parse_asn("AS15169"), Some(15169));
+/// assert_eq!(parse_asn(" asn 1.10 "), Some(65546));
+/// assert_eq!(parse_asn("google"), None);
+/// ```
+pub fn parse_asn(input: &str) -> Option<u32> {
+    let input = input.trim();
+    let number = match input.get(..2) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("AS") => {
+            let rest = &input[2..];
+            match rest.get(..1) {
+                Some(n) if n.eq_ignore_ascii_case("N") => &rest[1..],
+                _ => rest,
+            }
+        }
+        _ => input,
+    };
+    parse_asplain_or_asdot(number.trim_start())
+}
+
+/// Parse an ASN in asplain (`65546`) or asdot (`1.10`) notation.
+///
+/// In asdot notation, `X.Y` stands for `X * 65536 + Y` with both parts being 16-bit values.
+fn parse_asplain_or_asdot(asn: &str) -> Option<u32> {
+    match asn.split_once('.') {
+        Some((high, low)) => {
+            let high = high.parse::<u16>().ok()?;
+            let low = low.parse::<u16>().ok()?;
+            Some(((high as u32) << 16) | low as u32)
+        }
+        None => asn.parse::<u32>().ok(),
+    }
+}
+
+/// Number of lines between two progress callbacks of [As2org::new_with_progress].
+#[cfg(feature = "oneio")]
+pub const PROGRESS_INTERVAL: usize = 10_000;
+
+/// The `type` value of organization records
+const ORG_DATA_TYPE: &str = "Organization";
+/// The `type` value of AS records
+const AS_DATA_TYPE: &str = "ASN";
+
+/// A raw dataset entry, distinguished by its `type` field
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum As2orgEntry {
+    #[serde(rename = "Organization")]
+    Org(OrgRecord),
+    #[serde(rename = "ASN")]
+    As(AsRecord),
+}
+
+/// Information about an AS and its organization.
+///
+/// The serialized field names are the snake_case field names, and are kept stable for stored
+/// records and downstream JSON consumers.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub struct As2orgAsInfo {
     pub asn: u32,
     pub name: String,
@@ -111,41 +316,656 @@ pub struct As2orgAsInfo {
     pub org_id: String,
     pub org_name: String,
     pub source: String,
+    pub data_type: String,
+    pub opaque_id: Option<String>,
+    pub as_changed: Option<NaiveDate>,
+    pub org_changed: Option<NaiveDate>,
+}
+
+impl As2orgAsInfo {
+    /// Get the RIR of the database that contained this entry.
+    pub fn source_rir(&self) -> Rir {
+        Rir::from(self.source.as_str())
+    }
+
+    /// Check whether the country code is an officially assigned ISO 3166-1 alpha-2 code.
+    pub fn is_valid_country(&self) -> bool {
+        country::is_iso3166_alpha2(self.country_code.as_str())
+    }
+
+    /// Get the English name of the organization's country, e.g. `United States` for `US`.
+    ///
+    /// Returns `None` if the country code is empty or not an ISO 3166-1 alpha-2 code.
+    pub fn country_name(&self) -> Option<&'static str> {
+        country::country_name(self.country_code.as_str())
+    }
+}
+
+impl std::fmt::Display for As2orgAsInfo {
+    /// Format the AS as a one-line summary, e.g.
+    /// `AS15169 GOOGLE (org: GOGL-ARIN / Google LLC, US, ARIN)`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "AS{} {} (org: {} / {}, {}, {})",
+            self.asn, self.name, self.org_id, self.org_name, self.country_code, self.source
+        )
+    }
+}
+
+/// A borrowed view of an AS and its organization, returned by [As2org::get_as_info_ref].
+///
+/// It has the same fields as [As2orgAsInfo], but borrows the strings from the dataset instead of
+/// cloning them. Use [As2orgAsInfo::from] to get an owned copy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct As2orgAsInfoRef<'a> {
+    pub asn: u32,
+    pub name: &'a str,
+    pub country_code: &'a str,
+    pub org_id: &'a str,
+    pub org_name: &'a str,
+    pub source: &'a str,
+    pub data_type: &'a str,
+    pub opaque_id: Option<&'a str>,
+    pub as_changed: Option<NaiveDate>,
+    pub org_changed: Option<NaiveDate>,
+}
+
+impl From<As2orgAsInfoRef<'_>> for As2orgAsInfo {
+    fn from(info: As2orgAsInfoRef<'_>) -> Self {
+        As2orgAsInfo {
+            asn: info.asn,
+            name: info.name.to_string(),
+            country_code: info.country_code.to_string(),
+            org_id: info.org_id.to_string(),
+            org_name: info.org_name.to_string(),
+            source: info.source.to_string(),
+            data_type: info.data_type.to_string(),
+            opaque_id: info.opaque_id.map(String::from),
+            as_changed: info.as_changed,
+            org_changed: info.org_changed,
+        }
+    }
+}
+
+/// Regional Internet Registry (RIR) databases that AS2Org entries are sourced from.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Rir {
+    Arin,
+    Ripe,
+    Apnic,
+    Lacnic,
+    Afrinic,
+    /// other databases, such as National Internet Registries (NIRs), with the original source name
+    Other(String),
+}
+
+impl From<&str> for Rir {
+    /// Parse a `source` value case-insensitively.
+    fn from(source: &str) -> Self {
+        match source.trim().to_uppercase().as_str() {
+            "ARIN" => Rir::Arin,
+            "RIPE" | "RIPE NCC" => Rir::Ripe,
+            "APNIC" => Rir::Apnic,
+            "LACNIC" => Rir::Lacnic,
+            "AFRINIC" => Rir::Afrinic,
+            _ => Rir::Other(source.to_string()),
+        }
+    }
+}
+
+/// Public information about an organization.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct As2orgOrgInfo {
+    pub org_id: String,
+    pub name: String,
+    pub country: String,
+    pub source: String,
+    pub data_type: String,
+    pub changed: Option<NaiveDate>,
+}
+
+/// Differences between two loaded datasets, as returned by [As2org::diff].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct As2orgDiff {
+    /// ASNs only present in the newer dataset
+    pub asns_added: Vec<u32>,
+    /// ASNs only present in the older dataset
+    pub asns_removed: Vec<u32>,
+    /// ASNs present in both datasets whose organization changed
+    pub asns_changed: Vec<AsnOrgChange>,
+    /// organizations only present in the newer dataset
+    pub orgs_added: Vec<String>,
+    /// organizations only present in the older dataset
+    pub orgs_removed: Vec<String>,
+}
+
+/// An ASN whose organization changed between two datasets.
+///
+/// Organization names are empty if the organization record is missing from its dataset.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AsnOrgChange {
+    pub asn: u32,
+    pub old_org_id: String,
+    pub old_org_name: String,
+    pub new_org_id: String,
+    pub new_org_name: String,
+}
+
+/// A line of the input data that could not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParseError {
+    /// 1-based line number of the line in the input data
+    pub line: usize,
+    /// the parsing error message
+    pub message: String,
+}
+
+/// Numbers of ASes affected by [As2org::update_from].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpdateSummary {
+    /// number of ASes only present in the new dataset
+    pub asns_added: usize,
+    /// number of ASes no longer present in the new dataset
+    pub asns_removed: usize,
+    /// number of ASes whose AS or organization record differs in the new dataset
+    pub asns_changed: usize,
 }
 
+/// Summary statistics of a loaded AS2Org dataset, see [As2org::stats].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct As2orgStats {
+    /// number of ASes in the dataset
+    pub as_count: usize,
+    /// number of organizations in the dataset
+    pub org_count: usize,
+    /// number of ASes whose organization entry is missing from the dataset
+    pub orphan_as_count: usize,
+    /// number of distinct non-empty organization country codes
+    pub country_count: usize,
+    /// number of ASes per organization source (RIR or NIR), keyed by the uppercase source
+    pub as_count_by_source: BTreeMap<String, usize>,
+}
+
+/// An organization along with all of its ASes, see [As2org::get_sibling_group].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SiblingGroup {
+    /// the organization shared by the ASes
+    pub org: As2orgOrgInfo,
+    /// the ASes of the organization, sorted by ASN
+    pub members: Vec<As2orgAsInfo>,
+}
+
+/// Report of issues encountered while loading a dataset, see [As2org::new_with_report].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LoadReport {
+    /// ASNs with more than one AS record, sorted in ascending order
+    pub duplicate_asns: Vec<u32>,
+    /// organization IDs with more than one organization record, sorted alphabetically
+    pub duplicate_org_ids: Vec<String>,
+    /// lines that failed to parse and were skipped
+    pub skipped_lines: Vec<ParseError>,
+}
+
+/// The main lookup structure of a loaded AS2Org dataset.
+///
+/// The internal maps use the non-cryptographic `FxHash` hasher, which is considerably faster than
+/// the default SipHash for the small integer and short string keys looked up here.
+///
+/// The maps are shared behind [Arc]s, so cloning an `As2org` is cheap and the clones can be sent
+/// to other threads to query the same dataset concurrently.
+#[derive(Clone)]
 pub struct As2org {
-    as_map: HashMap<u32, As2orgJsonAs>,
-    org_map: HashMap<String, As2orgJsonOrg>,
-    as_to_org: HashMap<u32, String>,
-    org_to_as: HashMap<String, Vec<u32>>,
+    as_map: Arc<FxHashMap<u32, AsRecord>>,
+    org_map: Arc<FxHashMap<Arc<str>, OrgRecord>>,
+    as_to_org: Arc<FxHashMap<u32, Arc<str>>>,
+    org_to_as: Arc<FxHashMap<Arc<str>, Vec<u32>>>,
+    country_to_orgs: Arc<FxHashMap<Arc<str>, Vec<Arc<str>>>>,
+    source_to_orgs: Arc<FxHashMap<Arc<str>, Vec<Arc<str>>>>,
+    dataset_date: Option<NaiveDate>,
 }
 
 impl As2org {
+    #[cfg(feature = "oneio")]
     pub fn new(data_file_path: Option<String>) -> Result<Self> {
-        let entries = match data_file_path {
-            Some(path) => parse_as2org_file(path.as_str())?,
-            None => {
-                let url = get_most_recent_data()?;
-                parse_as2org_file(url.as_str())?
+        let (as2org, _errors) = Self::load(data_file_path, ParseOptions::default())?;
+        Ok(as2org)
+    }
+
+    /// Create an [As2orgBuilder] to configure the data source and loading options.
+    ///
+    /// ```no_run
+    /// use as2org_rs::As2org;
+    /// use chrono::NaiveDate;
+    ///
+    /// let as2org = As2org::builder()
+    ///     .date(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap())
+    ///     .lenient(true)
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    #[cfg(feature = "oneio")]
+    pub fn builder() -> As2orgBuilder {
+        As2orgBuilder::new()
+    }
+
+    /// Create a new `As2org` instance, skipping lines that fail to parse instead of aborting.
+    ///
+    /// Returns the loaded data along with a [ParseError] for each skipped line.
+    #[cfg(feature = "oneio")]
+    pub fn new_lenient(data_file_path: Option<String>) -> Result<(Self, Vec<ParseError>)> {
+        Self::load(data_file_path, ParseOptions::lenient())
+    }
+
+    /// Create a new `As2org` instance, reporting duplicate records and skipped lines.
+    ///
+    /// Lines that fail to parse are skipped as with [As2org::new_lenient]. Duplicate records are
+    /// resolved as usual, keeping the more recently changed record.
+    #[cfg(feature = "oneio")]
+    pub fn new_with_report(data_file_path: Option<String>) -> Result<(Self, LoadReport)> {
+        let path = match data_file_path {
+            Some(path) => path,
+            None => get_most_recent_data(BASE_URL)?,
+        };
+        let (entries, skipped_lines) = parse_as2org_file(path.as_str(), ParseOptions::lenient())?;
+
+        let mut seen_asns: FxHashSet<u32> = FxHashSet::default();
+        let mut seen_org_ids: FxHashSet<&str> = FxHashSet::default();
+        let mut duplicate_asns: FxHashSet<u32> = FxHashSet::default();
+        let mut duplicate_org_ids: FxHashSet<&str> = FxHashSet::default();
+        for entry in &entries {
+            match entry {
+                As2orgEntry::As(as_entry) => {
+                    if !seen_asns.insert(as_entry.asn) {
+                        duplicate_asns.insert(as_entry.asn);
+                    }
+                }
+                As2orgEntry::Org(org_entry) => {
+                    if !seen_org_ids.insert(org_entry.org_id.as_ref()) {
+                        duplicate_org_ids.insert(org_entry.org_id.as_ref());
+                    }
+                }
             }
+        }
+        let mut report = LoadReport {
+            duplicate_asns: duplicate_asns.into_iter().collect(),
+            duplicate_org_ids: duplicate_org_ids.into_iter().map(String::from).collect(),
+            skipped_lines,
+        };
+        report.duplicate_asns.sort_unstable();
+        report.duplicate_org_ids.sort();
+
+        let as2org = Self::from_entries(entries, parse_dataset_date(path.as_str()));
+        Ok((as2org, report))
+    }
+
+    /// Create a new `As2org` instance by merging the data files at the given paths.
+    ///
+    /// For ASes and organizations present in multiple files, the record with the more recent
+    /// `changed` date is kept, or the one from the later file if the dates are missing or equal.
+    /// The dataset date is the latest date parsed from the file names.
+    #[cfg(feature = "oneio")]
+    pub fn from_paths(paths: &[String]) -> Result<Self> {
+        let mut entries = vec![];
+        for path in paths {
+            let (path_entries, _errors) =
+                parse_as2org_file(path.as_str(), ParseOptions::default())?;
+            entries.extend(path_entries);
+        }
+        let dataset_date = paths
+            .iter()
+            .filter_map(|path| parse_dataset_date(path.as_str()))
+            .max();
+        Ok(Self::from_entries(entries, dataset_date))
+    }
+
+    #[cfg(feature = "oneio")]
+    fn load(
+        data_file_path: Option<String>,
+        options: ParseOptions,
+    ) -> Result<(Self, Vec<ParseError>)> {
+        let path = match data_file_path {
+            Some(path) => path,
+            None => get_most_recent_data(BASE_URL)?,
+        };
+        let (entries, errors) = parse_as2org_file(path.as_str(), options)?;
+        let dataset_date = parse_dataset_date(path.as_str());
+
+        Ok((Self::from_entries(entries, dataset_date), errors))
+    }
+
+    /// Create a new `As2org` instance, reporting the loading progress to the given callback.
+    ///
+    /// The callback is invoked with the number of lines read so far every
+    /// [PROGRESS_INTERVAL] lines, and once more with the total number of lines when the data file
+    /// has been read completely.
+    #[cfg(feature = "oneio")]
+    pub fn new_with_progress(
+        data_file_path: Option<String>,
+        mut progress: impl FnMut(usize),
+    ) -> Result<Self> {
+        let path = match data_file_path {
+            Some(path) => path,
+            None => get_most_recent_data(BASE_URL)?,
         };
+        let mut count = 0;
+        let lines = read_data_lines(path.as_str())?.inspect(|_| {
+            count += 1;
+            if count % PROGRESS_INTERVAL == 0 {
+                progress(count);
+            }
+        });
+        let res = parse_as2org_lines(lines, ParseOptions::default());
+        let (entries, _errors) =
+            ensure_records(path.as_str(), res, |(entries, _)| !entries.is_empty())?;
+        progress(count);
 
-        let mut as_map: HashMap<u32, As2orgJsonAs> = HashMap::new();
-        let mut org_map: HashMap<String, As2orgJsonOrg> = HashMap::new();
+        Ok(Self::from_entries(
+            entries,
+            parse_dataset_date(path.as_str()),
+        ))
+    }
 
-        for entry in entries {
+    /// Create a new `As2org` instance containing only the ASes in `keep` and their organizations.
+    ///
+    /// The data file is streamed and AS records of other ASNs are skipped, which greatly reduces
+    /// the memory usage when only a small subset of ASes is of interest. Queries for ASNs that
+    /// were not kept return `None` as if they were not in the dataset.
+    #[cfg(feature = "oneio")]
+    pub fn new_filtered(data_file_path: Option<String>, keep: &HashSet<u32>) -> Result<Self> {
+        let path = match data_file_path {
+            Some(path) => path,
+            None => get_most_recent_data(BASE_URL)?,
+        };
+        let mut as_entries = vec![];
+        let mut org_entries = vec![];
+        let mut count = 0;
+        let res = for_each_entry(path.as_str(), |entry| {
+            count += 1;
             match entry {
-                As2orgJsonEntry::As(as_entry) => {
-                    as_map.insert(as_entry.asn.parse::<u32>().unwrap(), as_entry);
-                }
-                As2orgJsonEntry::Org(org_entry) => {
-                    org_map.insert(org_entry.org_id.clone(), org_entry);
+                As2orgEntry::As(as_entry) if keep.contains(&as_entry.asn) => {
+                    as_entries.push(As2orgEntry::As(as_entry))
                 }
+                As2orgEntry::As(_) => {}
+                As2orgEntry::Org(org_entry) => org_entries.push(org_entry),
+            }
+        });
+        ensure_records(path.as_str(), res, |_| count > 0)?;
+
+        // organizations usually precede their ASes, so only drop them once all ASes are read
+        let org_ids: HashSet<&str> = as_entries
+            .iter()
+            .filter_map(|entry| match entry {
+                As2orgEntry::As(as_entry) => Some(as_entry.org_id.as_ref()),
+                As2orgEntry::Org(_) => None,
+            })
+            .collect();
+        let mut entries: Vec<As2orgEntry> = org_entries
+            .into_iter()
+            .filter(|org_entry| org_ids.contains(org_entry.org_id.as_ref()))
+            .map(As2orgEntry::Org)
+            .collect();
+        entries.extend(as_entries);
+
+        Ok(Self::from_entries(
+            entries,
+            parse_dataset_date(path.as_str()),
+        ))
+    }
+
+    /// Create a new `As2org` instance from the most recent dataset, caching the downloaded file in
+    /// `cache_dir`.
+    ///
+    /// A cached file younger than `max_age` is loaded directly. Otherwise the most recent dataset is
+    /// downloaded into the cache directory, keeping its `YYYYMMDD.as-org2info.jsonl.gz` file name so
+    /// that different snapshots do not overwrite each other. If the download fails, the newest
+    /// cached file is used regardless of its age.
+    #[cfg(feature = "oneio")]
+    pub fn new_cached(cache_dir: &str, max_age: Duration) -> Result<Self> {
+        Self::load_cached(cache_dir, max_age, BASE_URL)
+    }
+
+    #[cfg(feature = "oneio")]
+    fn load_cached(cache_dir: &str, max_age: Duration, base_url: &str) -> Result<Self> {
+        std::fs::create_dir_all(cache_dir)?;
+        let cached = find_cached_file(cache_dir);
+        if let Some(path) = &cached {
+            let is_fresh = std::fs::metadata(path)
+                .and_then(|meta| meta.modified())
+                .ok()
+                .and_then(|modified| modified.elapsed().ok())
+                .map(|age| age < max_age)
+                .unwrap_or(false);
+            if is_fresh {
+                return Self::new(Some(path.to_string_lossy().to_string()));
+            }
+        }
+
+        match download_latest_to_cache(cache_dir, base_url) {
+            Ok(path) => Self::new(Some(path.to_string_lossy().to_string())),
+            Err(e) => match cached {
+                Some(path) => Self::new(Some(path.to_string_lossy().to_string())),
+                None => Err(e),
+            },
+        }
+    }
+
+    /// Create a new `As2org` instance from the dataset published on the given date.
+    ///
+    /// Returns an error listing the nearest available dates if CAIDA did not publish a dataset on
+    /// that exact date.
+    #[cfg(feature = "oneio")]
+    pub fn from_date(date: NaiveDate) -> Result<Self> {
+        let files = Self::get_all_files_with_dates()?;
+        let (url, _) = find_file_on_date(&files, date)?;
+        Self::new(Some(url.clone()))
+    }
+
+    /// Get the date of the published dataset closest to the given date.
+    ///
+    /// If the given date is exactly between two datasets, the earlier one is returned.
+    #[cfg(feature = "oneio")]
+    pub fn nearest_date(date: NaiveDate) -> Result<NaiveDate> {
+        let files = Self::get_all_files_with_dates()?;
+        find_nearest_file(&files, date)
+            .map(|(_, d)| *d)
+            .ok_or_else(|| As2orgError::NoDatasets(BASE_URL.to_string()))
+    }
+
+    /// Get the URL of CAIDA's `latest.as-org2info.jsonl.gz` file, which always points to the most
+    /// recent dataset.
+    ///
+    /// The URL is constructed without any network request.
+    pub fn get_latest_file_url() -> String {
+        Self::get_latest_file_url_with("gz")
+    }
+
+    /// Get the URL of CAIDA's latest data file with the given compression extension, e.g. `zst`
+    /// for `latest.as-org2info.jsonl.zst`. A leading `.` in the extension is ignored.
+    pub fn get_latest_file_url_with(ext: &str) -> String {
+        format!(
+            "{BASE_URL}latest.as-org2info.jsonl.{}",
+            ext.trim_start_matches('.')
+        )
+    }
+
+    /// Download the data file at the given URL to `dest` without loading it.
+    ///
+    /// The file content is streamed to disk as-is, keeping its compression. Missing parent
+    /// directories of `dest` are created.
+    #[cfg(feature = "oneio")]
+    pub fn download_to(url: &str, dest: &str) -> Result<()> {
+        if let Some(parent) = Path::new(dest).parent() {
+            if !parent.as_os_str().is_empty() {
+                std::fs::create_dir_all(parent)?;
+            }
+        }
+        oneio::download(url, dest, None)?;
+        Ok(())
+    }
+
+    /// Get all as-org2info data files published by CAIDA along with their dataset dates, sorted by
+    /// date in ascending order.
+    ///
+    /// Files with the same date are sorted by URL, so the order does not depend on the order of
+    /// the directory listing.
+    #[cfg(feature = "oneio")]
+    pub fn get_all_files_with_dates() -> Result<Vec<(String, NaiveDate)>> {
+        Self::get_all_files_with_dates_from(BASE_URL)
+    }
+
+    /// Get the as-org2info data files published by CAIDA with dataset dates between `start` and
+    /// `end` (both inclusive), sorted by date in ascending order.
+    #[cfg(feature = "oneio")]
+    pub fn get_files_between(start: NaiveDate, end: NaiveDate) -> Result<Vec<(String, NaiveDate)>> {
+        let files = Self::get_all_files_with_dates()?;
+        Ok(filter_files_between(files, start, end))
+    }
+
+    /// Get the closest as-org2info data files published by CAIDA before and after the given date,
+    /// along with their dataset dates.
+    ///
+    /// A file published on the given date itself is not included. Either side is `None` if the
+    /// date is at the edge of the available range.
+    #[cfg(feature = "oneio")]
+    pub fn adjacent_files(date: NaiveDate) -> Result<(Option<DataFile>, Option<DataFile>)> {
+        let files = Self::get_all_files_with_dates()?;
+        let (before, after) = find_adjacent_files(&files, date);
+        Ok((before.cloned(), after.cloned()))
+    }
+
+    /// Get all as-org2info data files listed at the given base URL (e.g. an internal mirror of
+    /// CAIDA's dataset directory) along with their dataset dates, sorted by date in ascending
+    /// order.
+    #[cfg(feature = "oneio")]
+    pub fn get_all_files_with_dates_from(base_url: &str) -> Result<Vec<(String, NaiveDate)>> {
+        let base_url = match base_url.ends_with('/') {
+            true => base_url.to_string(),
+            false => format!("{base_url}/"),
+        };
+        let content = oneio::read_to_string(base_url.as_str())?;
+        Ok(parse_file_listing(content.as_str(), base_url.as_str()))
+    }
+
+    /// Create a new `As2org` instance from the data file at the given HTTP(S) URL, sending the given
+    /// headers with the request, e.g. an `Authorization` header for an authenticated proxy.
+    ///
+    /// See [As2orgBuilder::header] for further options.
+    #[cfg(feature = "oneio")]
+    pub fn new_from_url_with_headers(url: &str, headers: &[(String, String)]) -> Result<Self> {
+        headers
+            .iter()
+            .fold(Self::builder().source(url), |builder, (name, value)| {
+                builder.header(name.as_str(), value.as_str())
+            })
+            .build()
+    }
+
+    /// Create a new `As2org` instance from the most recent dataset listed at the given base URL
+    /// instead of CAIDA's public dataset directory.
+    #[cfg(feature = "oneio")]
+    pub fn new_from_mirror(base_url: &str) -> Result<Self> {
+        let url = get_most_recent_data(base_url)?;
+        Self::new(Some(url))
+    }
+
+    /// Create a new `As2org` instance from in-memory JSONL content.
+    ///
+    /// This uses the same parsing logic as [As2org::new], including the support for the older
+    /// JSON array format, but does not touch the filesystem or network, which is useful for tests
+    /// and bundled snapshots:
+    ///
+    /// ```rust
+    /// use as2org_rs::As2org;
+    ///
+    /// let content = r#"{"changed":"20240701","name":"Google LLC","country":"US","organizationId":"GOGL-ARIN","source":"ARIN","type":"Organization"}
+    /// {"changed":"20240701","asn":"15169","name":"GOOGLE","organizationId":"GOGL-ARIN","opaqueId":"","source":"ARIN","type":"ASN"}"#;
+    /// let as2org = As2org::from_jsonl_str(content).unwrap();
+    /// assert_eq!(as2org.get_as_info(15169).unwrap().org_name, "Google LLC");
+    /// ```
+    pub fn from_jsonl_str(content: &str) -> Result<Self> {
+        Self::from_reader(content.as_bytes())
+    }
+
+    /// Create a new `As2org` instance from any buffered reader of uncompressed JSONL content,
+    /// such as a decompressed stream, a [std::io::Cursor], or stdin.
+    pub fn from_reader<R: BufRead>(reader: R) -> Result<Self> {
+        let (entries, _errors) = parse_as2org_lines(reader.lines(), ParseOptions::default())?;
+        Ok(Self::from_entries(entries, None))
+    }
+
+    /// Create a new `As2org` instance from the in-memory content of a gzip-compressed JSONL data
+    /// file, e.g. one downloaded with a custom HTTP client.
+    pub fn from_gz_bytes(bytes: &[u8]) -> Result<Self> {
+        Self::from_reader(BufReader::new(GzDecoder::new(bytes)))
+    }
+
+    /// Save the loaded dataset to a cache file for fast reloading with [As2org::load_cache].
+    ///
+    /// The cache file starts with a header line containing the format version, followed by the
+    /// dataset entries in JSON. Compression is applied based on the file extension (e.g. `.gz`).
+    #[cfg(feature = "oneio")]
+    pub fn save_cache(&self, path: &str) -> Result<()> {
+        if let Some(parent) = Path::new(path).parent() {
+            if !parent.as_os_str().is_empty() {
+                std::fs::create_dir_all(parent)?;
             }
         }
+        let mut writer = oneio::get_writer(path)?;
+        let header = CacheHeader {
+            version: CACHE_FORMAT_VERSION,
+        };
+        serde_json::to_writer(&mut writer, &header)?;
+        writer.write_all(b"\n")?;
+        let data = CacheDataRef {
+            dataset_date: self.dataset_date,
+            as_entries: self.as_map.values().collect(),
+            org_entries: self.org_map.values().collect(),
+        };
+        serde_json::to_writer(&mut writer, &data)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Load a dataset from a cache file written by [As2org::save_cache].
+    ///
+    /// The lookup indices are rebuilt from the cached entries. Cache files written with a
+    /// different format version are rejected with [As2orgError::InvalidCache].
+    #[cfg(feature = "oneio")]
+    pub fn load_cache(path: &str) -> Result<Self> {
+        let mut lines = oneio::read_lines(path)?;
+        let header = match lines.next() {
+            Some(line) => serde_json::from_str::<CacheHeader>(line?.as_str())
+                .map_err(|_| As2orgError::InvalidCache("missing cache header".to_string()))?,
+            None => return Err(As2orgError::InvalidCache("empty cache file".to_string())),
+        };
+        if header.version != CACHE_FORMAT_VERSION {
+            return Err(As2orgError::InvalidCache(format!(
+                "unsupported cache format version {}, expected {}",
+                header.version, CACHE_FORMAT_VERSION
+            )));
+        }
+        let data: CacheData = match lines.next() {
+            Some(line) => serde_json::from_str(line?.as_str())?,
+            None => return Err(As2orgError::InvalidCache("missing cache data".to_string())),
+        };
 
-        let mut as_to_org: HashMap<u32, String> = HashMap::new();
-        let mut org_to_as: HashMap<String, Vec<u32>> = HashMap::new();
+        let entries = data
+            .as_entries
+            .into_iter()
+            .map(As2orgEntry::As)
+            .chain(data.org_entries.into_iter().map(As2orgEntry::Org))
+            .collect();
+        Ok(Self::from_entries(entries, data.dataset_date))
+    }
+
+    /// Build the lookup maps from parsed dataset entries.
+    fn from_entries(entries: Vec<As2orgEntry>, dataset_date: Option<NaiveDate>) -> Self {
+        let (as_map, org_map) = collect_records(entries);
+
+        // the index maps share the interned org_ids, countries and sources of the records
+        let mut as_to_org: FxHashMap<u32, Arc<str>> = FxHashMap::default();
+        let mut org_to_as: FxHashMap<Arc<str>, Vec<u32>> = FxHashMap::default();
 
         for (asn, as_entry) in as_map.iter() {
             as_to_org.insert(*asn, as_entry.org_id.clone());
@@ -153,39 +973,262 @@ impl As2org {
             org_asn.push(*asn);
         }
 
-        Ok(Self {
-            as_map,
-            org_map,
-            as_to_org,
-            org_to_as,
-        })
+        let mut country_to_orgs: FxHashMap<Arc<str>, Vec<Arc<str>>> = FxHashMap::default();
+        let mut source_to_orgs: FxHashMap<Arc<str>, Vec<Arc<str>>> = FxHashMap::default();
+        for (org_id, org_entry) in org_map.iter() {
+            let country_orgs = country_to_orgs
+                .entry(org_entry.country.clone())
+                .or_default();
+            country_orgs.push(org_id.clone());
+            let source_orgs = source_to_orgs
+                .entry(source_key(&org_entry.source))
+                .or_default();
+            source_orgs.push(org_id.clone());
+        }
+
+        Self {
+            as_map: Arc::new(as_map),
+            org_map: Arc::new(org_map),
+            as_to_org: Arc::new(as_to_org),
+            org_to_as: Arc::new(org_to_as),
+            country_to_orgs: Arc::new(country_to_orgs),
+            source_to_orgs: Arc::new(source_to_orgs),
+            dataset_date,
+        }
+    }
+
+    /// Get the date of the loaded dataset.
+    ///
+    /// The date is parsed from the `YYYYMMDD.as-org2info` file name of the loaded file, and is
+    /// `None` if the file name does not follow CAIDA's naming scheme.
+    pub fn dataset_date(&self) -> Option<NaiveDate> {
+        self.dataset_date
     }
 
     pub fn get_as_info(&self, asn: u32) -> Option<As2orgAsInfo> {
         let as_entry = self.as_map.get(&asn)?;
-        let org_id = as_entry.org_id.as_str();
+        let org_entry = self.org_map.get(as_entry.org_id.as_ref())?;
+        Some(build_as_info(as_entry, Some(org_entry)))
+    }
+
+    /// Get information about an AS as a borrowed view into the dataset.
+    ///
+    /// This avoids cloning the strings of [As2org::get_as_info], which matters in tight loops over
+    /// many ASNs. Returns `None` under the same conditions.
+    pub fn get_as_info_ref(&self, asn: u32) -> Option<As2orgAsInfoRef<'_>> {
+        let as_entry = self.as_map.get(&asn)?;
+        let org_entry = self.org_map.get(as_entry.org_id.as_ref())?;
+        Some(As2orgAsInfoRef {
+            asn: as_entry.asn,
+            name: as_entry.name.as_str(),
+            country_code: org_entry.country.as_ref(),
+            org_id: as_entry.org_id.as_ref(),
+            org_name: org_entry.name.as_str(),
+            source: org_entry.source.as_ref(),
+            data_type: AS_DATA_TYPE,
+            opaque_id: as_entry.opaque_id.as_deref(),
+            as_changed: parse_changed_date(as_entry.changed.as_deref()),
+            org_changed: parse_changed_date(org_entry.changed.as_deref()),
+        })
+    }
+
+    /// Get information about an AS, returning the reason if it cannot be found.
+    ///
+    /// Unlike [As2org::get_as_info], this distinguishes ASNs that are not in the dataset from
+    /// ASes whose organization entry is missing, e.g. for user-facing error messages.
+    pub fn lookup(&self, asn: u32) -> std::result::Result<As2orgAsInfo, LookupError> {
+        let as_entry = self.as_map.get(&asn).ok_or(LookupError::UnknownAsn(asn))?;
+        let org_entry =
+            self.org_map
+                .get(as_entry.org_id.as_ref())
+                .ok_or_else(|| LookupError::MissingOrg {
+                    asn,
+                    org_id: as_entry.org_id.to_string(),
+                })?;
+        Ok(build_as_info(as_entry, Some(org_entry)))
+    }
+
+    /// Get information about an AS, even if its organization entry is missing from the dataset.
+    ///
+    /// For such orphan ASes, `org_name` and `country_code` are empty, `org_changed` is `None`, and
+    /// `source` is taken from the AS entry. Returns `None` only if the ASN is not found.
+    pub fn get_as_info_partial(&self, asn: u32) -> Option<As2orgAsInfo> {
+        let as_entry = self.as_map.get(&asn)?;
+        let org_entry = self.org_map.get(as_entry.org_id.as_ref());
+        Some(build_as_info(as_entry, org_entry))
+    }
+
+    /// Get information about an organization by its `org_id`.
+    ///
+    /// Returns `None` if the organization is not present in the dataset.
+    pub fn get_org_info(&self, org_id: &str) -> Option<As2orgOrgInfo> {
         let org_entry = self.org_map.get(org_id)?;
-        Some(As2orgAsInfo {
-            asn,
-            name: as_entry.name.clone(),
-            country_code: org_entry.country.clone(),
-            org_id: org_id.to_string(),
-            org_name: org_entry.name.clone(),
-            source: org_entry.source.clone(),
+        Some(As2orgOrgInfo {
+            org_id: org_entry.org_id.to_string(),
+            name: org_entry.name.clone(),
+            country: org_entry.country.to_string(),
+            source: org_entry.source.to_string(),
+            data_type: ORG_DATA_TYPE.to_string(),
+            changed: parse_changed_date(org_entry.changed.as_deref()),
         })
     }
 
+    /// Get the normalized country code of the given ASN's organization.
+    ///
+    /// Returns `None` if the ASN or its organization entry is not found.
+    pub fn get_country(&self, asn: u32) -> Option<String> {
+        Some(self.org_entry_of_asn(asn)?.country.to_string())
+    }
+
+    /// Get the name of the given ASN's organization.
+    ///
+    /// Returns `None` if the ASN or its organization entry is not found.
+    pub fn get_org_name(&self, asn: u32) -> Option<String> {
+        Some(self.org_entry_of_asn(asn)?.name.clone())
+    }
+
+    fn org_entry_of_asn(&self, asn: u32) -> Option<&OrgRecord> {
+        let org_id = self.as_to_org.get(&asn)?;
+        self.org_map.get(org_id.as_ref())
+    }
+
+    /// Get information about an organization along with its number of ASes.
+    ///
+    /// Organizations without any AS in the dataset have a count of `0`. Returns `None` if the
+    /// organization is not present in the dataset.
+    pub fn get_org_with_size(&self, org_id: &str) -> Option<(As2orgOrgInfo, usize)> {
+        let org = self.get_org_info(org_id)?;
+        let size = self.org_to_as.get(org_id).map_or(0, |asns| asns.len());
+        Some((org, size))
+    }
+
+    /// Get all sibling ASes of the given ASN, sorted by ASN.
+    ///
+    /// The given ASN itself is included in its sorted position. ASes whose organization entry is
+    /// missing from the dataset are skipped.
     pub fn get_siblings(&self, asn: u32) -> Option<Vec<As2orgAsInfo>> {
         let org_id = self.as_to_org.get(&asn)?;
-        let org_asns = self.org_to_as.get(org_id)?.to_vec();
+        let org_asns = self.org_to_as.get(org_id)?;
+        let mut res: Vec<As2orgAsInfo> = org_asns
+            .iter()
+            .filter_map(|asn| self.get_as_info(*asn))
+            .collect();
+        res.sort_by_key(|info| info.asn);
+        Some(res)
+    }
+
+    /// Get the sibling relationships of the given ASN as graph edges.
+    ///
+    /// The edges form a star from the given ASN to each of its siblings as returned by
+    /// [As2org::get_siblings], i.e. `(asn, sibling)` pairs sorted by the sibling ASN, without a
+    /// self-loop. The clique among all siblings can be built by combining the stars of the
+    /// siblings. Returns `None` if the ASN is not found.
+    pub fn sibling_edges(&self, asn: u32) -> Option<Vec<(u32, u32)>> {
+        let siblings = self.get_siblings(asn)?;
         Some(
-            org_asns
+            siblings
                 .iter()
-                .map(|asn| self.get_as_info(*asn).unwrap())
+                .filter(|info| info.asn != asn)
+                .map(|info| (asn, info.asn))
+                .collect(),
+        )
+    }
+
+    /// Get the sibling ASes of the given ASN whose own AS record is sourced from the given RIR or
+    /// NIR database (e.g. `RIPE`), sorted by ASN.
+    ///
+    /// Unlike `source` in [As2orgAsInfo], which is taken from the shared organization entry, this
+    /// matches the `source` of each AS record case-insensitively, which segments the ASes of a
+    /// multi-RIR operator by registry. Returns `None` if the ASN is not found.
+    pub fn get_siblings_by_source(&self, asn: u32, source: &str) -> Option<Vec<As2orgAsInfo>> {
+        let siblings = self.get_siblings(asn)?;
+        Some(
+            siblings
+                .into_iter()
+                .filter(|info| {
+                    self.as_map
+                        .get(&info.asn)
+                        .is_some_and(|as_entry| as_entry.source.eq_ignore_ascii_case(source))
+                })
                 .collect(),
         )
     }
 
+    /// Get the organization of the given ASN along with all of its ASes, sorted by ASN.
+    ///
+    /// Returns `None` if the ASN is not found or its organization entry is missing.
+    pub fn get_sibling_group(&self, asn: u32) -> Option<SiblingGroup> {
+        let org_id = self.as_to_org.get(&asn)?;
+        self.sibling_group_of_org(org_id)
+    }
+
+    /// Group the given ASNs by the `org_id` of their organization.
+    ///
+    /// The ASNs of each group keep their input order, with repeated ASNs (e.g. from AS path
+    /// prepending) included once. ASNs that are not in the dataset are dropped.
+    pub fn group_by_org(&self, asns: &[u32]) -> HashMap<String, Vec<u32>> {
+        let mut groups: HashMap<String, Vec<u32>> = HashMap::new();
+        for asn in asns {
+            let Some(org_id) = self.as_to_org.get(asn) else {
+                continue;
+            };
+            let group = groups.entry(org_id.to_string()).or_default();
+            if !group.contains(asn) {
+                group.push(*asn);
+            }
+        }
+        groups
+    }
+
+    /// Iterate over the sibling groups of all organizations with ASes in the dataset.
+    ///
+    /// The groups are produced lazily in arbitrary order, including those of organizations with a
+    /// single AS. ASes whose organization entry is missing are skipped.
+    pub fn iter_sibling_groups(&self) -> impl Iterator<Item = SiblingGroup> + '_ {
+        self.org_to_as
+            .keys()
+            .filter_map(|org_id| self.sibling_group_of_org(org_id))
+    }
+
+    fn sibling_group_of_org(&self, org_id: &str) -> Option<SiblingGroup> {
+        let org = self.get_org_info(org_id)?;
+        let mut members: Vec<As2orgAsInfo> = self
+            .org_to_as
+            .get(org_id)?
+            .iter()
+            .filter_map(|asn| self.get_as_info(*asn))
+            .collect();
+        members.sort_by_key(|info| info.asn);
+        Some(SiblingGroup { org, members })
+    }
+
+    /// Get the number of sibling ASes of the given ASN, including the ASN itself, without building
+    /// the list of siblings.
+    ///
+    /// The count matches the length of [As2org::get_siblings], so it is zero for ASes whose
+    /// organization entry is missing. Returns `None` if the ASN is not found.
+    pub fn sibling_count(&self, asn: u32) -> Option<usize> {
+        let org_id = self.as_to_org.get(&asn)?;
+        if !self.org_map.contains_key(org_id) {
+            return Some(0);
+        }
+        self.org_to_as.get(org_id).map(|asns| asns.len())
+    }
+
+    /// Get all sibling ASes of the given ASN, excluding the ASN itself, sorted by ASN.
+    ///
+    /// Returns an empty vector if the ASN is the only AS of its organization, and `None` if the
+    /// ASN is not found.
+    pub fn get_siblings_excluding_self(&self, asn: u32) -> Option<Vec<As2orgAsInfo>> {
+        let mut res = self.get_siblings(asn)?;
+        res.retain(|info| info.asn != asn);
+        Some(res)
+    }
+
+    /// Check whether two ASes belong to the same organization.
+    ///
+    /// Returns `false` if either ASN is not found. Comparing an ASN present in the dataset with
+    /// itself returns `true`; use [As2org::are_distinct_siblings] to exclude self-comparisons.
     pub fn are_siblings(&self, asn1: u32, asn2: u32) -> bool {
         let org1 = match self.as_to_org.get(&asn1) {
             None => return false,
@@ -197,65 +1240,2814 @@ impl As2org {
         };
         org1 == org2
     }
-}
 
-/// parse remote AS2Org file into Vec of DataEntry
-fn parse_as2org_file(path: &str) -> Result<Vec<As2orgJsonEntry>> {
-    let mut res: Vec<As2orgJsonEntry> = vec![];
+    /// Check whether two *different* ASes belong to the same organization.
+    ///
+    /// Unlike [As2org::are_siblings], this returns `false` when `asn1 == asn2`, which is usually
+    /// the desired semantics when building sibling graphs from pairwise comparisons.
+    pub fn are_distinct_siblings(&self, asn1: u32, asn2: u32) -> bool {
+        asn1 != asn2 && self.are_siblings(asn1, asn2)
+    }
 
-    for line in oneio::read_lines(path)? {
-        let line = line?;
-        if line.contains(r#""type":"ASN""#) {
-            let data = serde_json::from_str::<As2orgJsonAs>(line.as_str());
-            match data {
-                Ok(data) => {
-                    res.push(As2orgJsonEntry::As(data));
-                }
-                Err(e) => {
-                    eprintln!("error parsing line:\n{}", line.as_str());
-                    return Err(anyhow!(e));
-                }
-            }
-        } else {
-            let data = serde_json::from_str::<As2orgJsonOrg>(line.as_str());
-            match data {
-                Ok(data) => {
-                    res.push(As2orgJsonEntry::Org(data));
-                }
-                Err(e) => {
-                    eprintln!("error parsing line:\n{}", line.as_str());
-                    return Err(anyhow!(e));
-                }
-            }
+    /// Check whether two ASes have identical sibling sets, comparing the ASNs returned by
+    /// [As2org::get_siblings].
+    ///
+    /// With siblings grouped by `org_id` this is equivalent to [As2org::are_siblings]. Returns
+    /// `false` if either ASN is not found or has no siblings with an organization entry.
+    pub fn same_sibling_set(&self, a: u32, b: u32) -> bool {
+        let sibling_asns = |asn: u32| -> Option<Vec<u32>> {
+            let siblings = self.get_siblings(asn)?;
+            Some(siblings.iter().map(|info| info.asn).collect())
+        };
+        match (sibling_asns(a), sibling_asns(b)) {
+            (Some(a), Some(b)) => !a.is_empty() && a == b,
+            _ => false,
         }
     }
-    Ok(res)
-}
 
-/// Get the most recent AS2Org data file from CAIDA
-fn get_most_recent_data() -> Result<String> {
-    let data_link: Regex = Regex::new(r".*(........\.as-org2info\.jsonl\.gz).*")?;
-    let content = oneio::read_to_string("https://publicdata.caida.org/datasets/as-organizations/")?;
-    let res: Vec<String> = data_link
-        .captures_iter(content.as_str())
-        .map(|cap| cap[1].to_owned())
-        .collect();
-    let file = res.last().unwrap().to_string();
+    /// Check whether the organizations of two ASes have the same name after normalization
+    /// (trimming, lowercasing and collapsing whitespace).
+    ///
+    /// This is a looser heuristic than [As2org::are_siblings]: it also matches an operator whose
+    /// organization is registered in multiple RIR databases under different `org_id`s, but may
+    /// match unrelated organizations with generic names. Returns `false` if either ASN or its
+    /// organization entry is not found, or if the names are empty.
+    pub fn are_siblings_by_name(&self, asn1: u32, asn2: u32) -> bool {
+        let org_name = |asn: u32| {
+            let org_id = self.as_to_org.get(&asn)?;
+            let org_entry = self.org_map.get(org_id.as_ref())?;
+            Some(normalize_org_name(org_entry.name.as_str()))
+        };
+        match (org_name(asn1), org_name(asn2)) {
+            (Some(name1), Some(name2)) => !name1.is_empty() && name1 == name2,
+            _ => false,
+        }
+    }
 
-    Ok(format!(
-        "https://publicdata.caida.org/datasets/as-organizations/{file}"
-    ))
-}
+    /// Get all ASes whose organization is registered in the given country, sorted by ASN.
+    ///
+    /// The country code is matched case-insensitively. Returns an empty vector for unknown codes.
+    pub fn get_asns_by_country(&self, cc: &str) -> Vec<As2orgAsInfo> {
+        self.get_asns_of_orgs(self.country_to_orgs.get(cc.to_uppercase().as_str()))
+    }
 
-#[cfg(test)]
+    /// Get all ASes whose organization entry is sourced from the given RIR or NIR database
+    /// (e.g. `ARIN`), sorted by ASN.
+    ///
+    /// The source is matched case-insensitively. Returns an empty vector for unknown sources.
+    pub fn get_asns_by_source(&self, rir: &str) -> Vec<As2orgAsInfo> {
+        self.get_asns_of_orgs(self.source_to_orgs.get(rir.to_uppercase().as_str()))
+    }
+
+    /// Get all ASes of the organizations with the given name, sorted by ASN.
+    ///
+    /// The name is matched exactly but case-insensitively. As organization names are not unique,
+    /// the ASes of all organizations sharing the name are included.
+    pub fn get_asns_by_org_name(&self, name: &str) -> Vec<As2orgAsInfo> {
+        let name = name.to_lowercase();
+        let org_ids: Vec<Arc<str>> = self
+            .org_map
+            .values()
+            .filter(|org_entry| org_entry.name.to_lowercase() == name)
+            .map(|org_entry| org_entry.org_id.clone())
+            .collect();
+        self.get_asns_of_orgs(Some(&org_ids))
+    }
+
+    /// Get the organization names shared by more than one organization, mapped to the IDs of the
+    /// organizations with that name, sorted alphabetically.
+    ///
+    /// Names are compared exactly. This surfaces operators whose organization is registered in
+    /// multiple RIR databases.
+    pub fn orgs_sharing_name(&self) -> HashMap<String, Vec<String>> {
+        let mut name_to_orgs: HashMap<String, Vec<String>> = HashMap::new();
+        for org_entry in self.org_map.values() {
+            name_to_orgs
+                .entry(org_entry.name.clone())
+                .or_default()
+                .push(org_entry.org_id.to_string());
+        }
+        name_to_orgs.retain(|_, org_ids| org_ids.len() > 1);
+        for org_ids in name_to_orgs.values_mut() {
+            org_ids.sort();
+        }
+        name_to_orgs
+    }
+
+    /// Get all ASes of the given organizations, sorted by ASN.
+    fn get_asns_of_orgs<S: AsRef<str>>(&self, org_ids: Option<&Vec<S>>) -> Vec<As2orgAsInfo> {
+        let mut res: Vec<As2orgAsInfo> = org_ids
+            .into_iter()
+            .flatten()
+            .filter_map(|org_id| self.org_to_as.get(org_id.as_ref()))
+            .flatten()
+            .filter_map(|asn| self.get_as_info(*asn))
+            .collect();
+        res.sort_by_key(|info| info.asn);
+        res
+    }
+
+    /// Get the distinct country codes of all organizations, sorted alphabetically.
+    ///
+    /// Empty codes and codes that are not ISO 3166-1 alpha-2 codes are excluded.
+    pub fn countries(&self) -> Vec<String> {
+        let mut res: Vec<String> = self
+            .country_to_orgs
+            .keys()
+            .filter(|cc| country::is_iso3166_alpha2(cc))
+            .map(|cc| cc.to_string())
+            .collect();
+        res.sort();
+        res
+    }
+
+    /// Get the number of ASes per country code of their organization, keyed by the uppercased
+    /// country code.
+    ///
+    /// Organizations with an empty country code are excluded, as are countries without any AS.
+    pub fn asn_count_by_country(&self) -> BTreeMap<String, usize> {
+        let mut res = BTreeMap::new();
+        for (cc, org_ids) in self.country_to_orgs.iter() {
+            if cc.is_empty() {
+                continue;
+            }
+            let count: usize = org_ids
+                .iter()
+                .filter_map(|org_id| self.org_to_as.get(org_id))
+                .map(|asns| asns.len())
+                .sum();
+            if count > 0 {
+                res.insert(cc.to_string(), count);
+            }
+        }
+        res
+    }
+
+    /// Get the distinct sources (RIR or NIR databases) of all organizations, uppercased and sorted
+    /// alphabetically.
+    pub fn sources(&self) -> Vec<String> {
+        let mut res: Vec<String> = self
+            .source_to_orgs
+            .keys()
+            .filter(|source| !source.is_empty())
+            .map(|source| source.to_string())
+            .collect();
+        res.sort();
+        res
+    }
+
+    /// Get all organizations registered in the given country, sorted by `org_id`.
+    ///
+    /// The country code is matched case-insensitively. Returns an empty vector for unknown codes.
+    pub fn get_orgs_by_country(&self, cc: &str) -> Vec<As2orgOrgInfo> {
+        let mut res: Vec<As2orgOrgInfo> = self
+            .country_to_orgs
+            .get(cc.to_uppercase().as_str())
+            .into_iter()
+            .flatten()
+            .filter_map(|org_id| self.get_org_info(org_id))
+            .collect();
+        res.sort_by(|a, b| a.org_id.cmp(&b.org_id));
+        res
+    }
+
+    /// Search organizations whose name contains the given query, case-insensitively.
+    ///
+    /// The results are sorted by name, then by `org_id`.
+    pub fn search_orgs_by_name(&self, query: &str) -> Vec<As2orgOrgInfo> {
+        let query = query.to_lowercase();
+        let mut res: Vec<As2orgOrgInfo> = self
+            .org_map
+            .iter()
+            .filter(|(_, org_entry)| org_entry.name.to_lowercase().contains(&query))
+            .filter_map(|(org_id, _)| self.get_org_info(org_id))
+            .collect();
+        res.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.org_id.cmp(&b.org_id)));
+        res
+    }
+
+    /// Search organizations whose name is within `max_distance` edits of the given query.
+    ///
+    /// The distance is the Levenshtein distance between the lowercased query and organization
+    /// name. The results are paired with their distances and sorted by distance, then by name and
+    /// `org_id`.
+    pub fn fuzzy_search_orgs(
+        &self,
+        query: &str,
+        max_distance: usize,
+    ) -> Vec<(As2orgOrgInfo, usize)> {
+        let query: Vec<char> = query.to_lowercase().chars().collect();
+        let mut res: Vec<(As2orgOrgInfo, usize)> = self
+            .org_map
+            .iter()
+            .filter_map(|(org_id, org_entry)| {
+                let name: Vec<char> = org_entry.name.to_lowercase().chars().collect();
+                if name.len().abs_diff(query.len()) > max_distance {
+                    return None;
+                }
+                let distance = levenshtein(&query, &name);
+                match distance <= max_distance {
+                    true => Some((self.get_org_info(org_id)?, distance)),
+                    false => None,
+                }
+            })
+            .collect();
+        res.sort_by(|a, b| {
+            a.1.cmp(&b.1)
+                .then_with(|| a.0.name.cmp(&b.0.name))
+                .then_with(|| a.0.org_id.cmp(&b.0.org_id))
+        });
+        res
+    }
+
+    /// Search ASes whose own AS name contains the given query, case-insensitively.
+    ///
+    /// Unlike [As2org::search_orgs_by_name], this matches the name registered for the individual
+    /// AS (e.g. `GOOGLE-BACKBONE`) rather than its organization name. The results are sorted by ASN.
+    pub fn find_asns_by_name(&self, query: &str) -> Vec<As2orgAsInfo> {
+        let query = query.to_lowercase();
+        let mut res: Vec<As2orgAsInfo> = self
+            .as_map
+            .iter()
+            .filter(|(_, as_entry)| as_entry.name.to_lowercase().contains(&query))
+            .filter_map(|(asn, _)| self.get_as_info(*asn))
+            .collect();
+        res.sort_by_key(|info| info.asn);
+        res
+    }
+
+    /// Get information about many ASes at once.
+    ///
+    /// Returns a map from ASN to its information, skipping ASNs that are not found.
+    pub fn get_as_info_batch(&self, asns: &[u32]) -> HashMap<u32, As2orgAsInfo> {
+        asns.iter()
+            .filter_map(|asn| Some((*asn, self.get_as_info(*asn)?)))
+            .collect()
+    }
+
+    /// Iterate over the information of all ASes in the dataset.
+    ///
+    /// The information is produced lazily in arbitrary order. ASes whose organization entry is
+    /// missing from the dataset are skipped.
+    pub fn iter_as_info(&self) -> impl Iterator<Item = As2orgAsInfo> + '_ {
+        self.as_map.keys().filter_map(|asn| self.get_as_info(*asn))
+    }
+
+    /// Write the information of all ASes in the dataset as CSV, ordered by ASN.
+    ///
+    /// The output starts with the header row `asn,name,country_code,org_id,org_name,source`.
+    /// Fields containing commas, quotes or line breaks are quoted. ASes whose organization entry
+    /// is missing are included with empty organization fields.
+    pub fn write_csv<W: Write>(&self, mut w: W) -> Result<()> {
+        writeln!(w, "asn,name,country_code,org_id,org_name,source")?;
+        for info in self.sorted_as_info() {
+            writeln!(
+                w,
+                "{},{},{},{},{},{}",
+                info.asn,
+                csv_field(info.name.as_str()),
+                csv_field(info.country_code.as_str()),
+                csv_field(info.org_id.as_str()),
+                csv_field(info.org_name.as_str()),
+                csv_field(info.source.as_str()),
+            )?;
+        }
+        w.flush()?;
+        Ok(())
+    }
+
+    /// Serialize the information of all ASes in the dataset as a JSON array, ordered by ASN.
+    ///
+    /// ASes whose organization entry is missing are included with empty organization fields.
+    pub fn to_enriched_json(&self) -> Result<String> {
+        Ok(serde_json::to_string(&self.sorted_as_info())?)
+    }
+
+    /// Get the (partial) information of all ASes in the dataset, sorted by ASN.
+    fn sorted_as_info(&self) -> Vec<As2orgAsInfo> {
+        let mut asns: Vec<u32> = self.as_map.keys().copied().collect();
+        asns.sort_unstable();
+        asns.into_iter()
+            .filter_map(|asn| self.get_as_info_partial(asn))
+            .collect()
+    }
+
+    /// Iterate over the raw records of all organizations and ASes in the dataset.
+    ///
+    /// All organization records are produced before the AS records, each in arbitrary order.
+    /// Country codes of organization records are normalized to trimmed uppercase.
+    pub fn entries(&self) -> impl Iterator<Item = As2orgEntry> + '_ {
+        let orgs = self.org_map.values().cloned().map(As2orgEntry::Org);
+        let ases = self.as_map.values().cloned().map(As2orgEntry::As);
+        orgs.chain(ases)
+    }
+
+    /// Get the number of ASes in the dataset.
+    pub fn as_count(&self) -> usize {
+        self.as_map.len()
+    }
+
+    /// Get the number of organizations in the dataset.
+    pub fn org_count(&self) -> usize {
+        self.org_map.len()
+    }
+
+    /// Check whether the dataset contains neither ASes nor organizations.
+    pub fn is_empty(&self) -> bool {
+        self.as_map.is_empty() && self.org_map.is_empty()
+    }
+
+    /// Check whether the given ASN is present in the dataset.
+    pub fn contains_asn(&self, asn: u32) -> bool {
+        self.as_map.contains_key(&asn)
+    }
+
+    /// Check whether the given organization is present in the dataset.
+    pub fn contains_org(&self, org_id: &str) -> bool {
+        self.org_map.contains_key(org_id)
+    }
+
+    /// Get summary statistics of the dataset.
+    pub fn stats(&self) -> As2orgStats {
+        let as_count_by_source = self
+            .source_to_orgs
+            .iter()
+            .map(|(source, org_ids)| {
+                let count = org_ids
+                    .iter()
+                    .filter_map(|org_id| self.org_to_as.get(org_id))
+                    .map(|asns| asns.len())
+                    .sum();
+                (source.to_string(), count)
+            })
+            .collect();
+        As2orgStats {
+            as_count: self.as_map.len(),
+            org_count: self.org_map.len(),
+            orphan_as_count: self
+                .as_map
+                .values()
+                .filter(|as_entry| !self.org_map.contains_key(&as_entry.org_id))
+                .count(),
+            country_count: self
+                .country_to_orgs
+                .keys()
+                .filter(|country| !country.is_empty())
+                .count(),
+            as_count_by_source,
+        }
+    }
+
+    /// Get the distribution of organization sizes, mapping a number of ASes to the number of
+    /// organizations with that many ASes.
+    ///
+    /// Organizations without any AS in the dataset are not counted.
+    pub fn org_size_histogram(&self) -> BTreeMap<usize, usize> {
+        let mut histogram = BTreeMap::new();
+        for (org_id, asns) in self.org_to_as.iter() {
+            if self.org_map.contains_key(org_id.as_ref()) {
+                *histogram.entry(asns.len()).or_insert(0) += 1;
+            }
+        }
+        histogram
+    }
+
+    /// Get the ASNs whose organization entry is missing from the dataset, sorted in ascending order.
+    ///
+    /// These ASes are present in the dataset, but [get_as_info](Self::get_as_info) returns `None`
+    /// for them.
+    pub fn orphan_asns(&self) -> Vec<u32> {
+        let mut asns: Vec<u32> = self
+            .as_map
+            .values()
+            .filter(|as_entry| !self.org_map.contains_key(&as_entry.org_id))
+            .map(|as_entry| as_entry.asn)
+            .collect();
+        asns.sort_unstable();
+        asns
+    }
+
+    /// Get the ASNs whose AS-level `source` differs from the `source` of their organization,
+    /// sorted in ascending order.
+    ///
+    /// Sources are compared case-insensitively. Such records can indicate cross-RIR transfers or
+    /// data oddities. ASes whose organization entry is missing are not included.
+    pub fn inconsistent_records(&self) -> Vec<u32> {
+        let mut asns: Vec<u32> = self
+            .as_map
+            .values()
+            .filter(|as_entry| {
+                self.org_map
+                    .get(as_entry.org_id.as_ref())
+                    .is_some_and(|org| !org.source.eq_ignore_ascii_case(&as_entry.source))
+            })
+            .map(|as_entry| as_entry.asn)
+            .collect();
+        asns.sort_unstable();
+        asns
+    }
+
+    /// Get the `n` organizations with the most ASes, along with their AS counts.
+    ///
+    /// Organizations are ranked by AS count in descending order, with ties broken by `org_id`.
+    pub fn top_orgs_by_asn_count(&self, n: usize) -> Vec<(As2orgOrgInfo, usize)> {
+        let mut counts: Vec<(&str, usize)> = self
+            .org_to_as
+            .iter()
+            .filter(|(org_id, _)| self.org_map.contains_key(org_id.as_ref()))
+            .map(|(org_id, asns)| (org_id.as_ref(), asns.len()))
+            .collect();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        counts
+            .into_iter()
+            .take(n)
+            .filter_map(|(org_id, count)| Some((self.get_org_info(org_id)?, count)))
+            .collect()
+    }
+
+    /// Get the organization with the most ASes, with ties broken by `org_id`.
+    ///
+    /// This is a rough proxy for the largest operator in the dataset. Returns `None` if there are
+    /// no organizations with ASes.
+    pub fn largest_org_by_asn_count(&self) -> Option<As2orgOrgInfo> {
+        let (org, _count) = self.top_orgs_by_asn_count(1).pop()?;
+        Some(org)
+    }
+
+    /// Update the loaded dataset to the data file at the given path, returning how many ASes
+    /// were added, removed or changed.
+    ///
+    /// The new data file is parsed completely before applying any changes, so `self` is left
+    /// unchanged if loading fails. Only the added, removed and changed records and their index
+    /// entries are touched. Clones of the previous dataset keep referring to the old data, in which
+    /// case the affected maps are copied before updating them.
+    #[cfg(feature = "oneio")]
+    pub fn update_from(&mut self, path: &str) -> Result<UpdateSummary> {
+        let (entries, _errors) = parse_as2org_file(path, ParseOptions::default())?;
+        let (new_as_map, new_org_map) = collect_records(entries);
+
+        let mut summary = UpdateSummary::default();
+        for (asn, as_entry) in new_as_map.iter() {
+            match self.as_map.get(asn) {
+                None => summary.asns_added += 1,
+                Some(old_entry) => {
+                    let old_org = self.org_map.get(old_entry.org_id.as_ref());
+                    let new_org = new_org_map.get(as_entry.org_id.as_ref());
+                    if old_entry != as_entry || old_org != new_org {
+                        summary.asns_changed += 1;
+                    }
+                }
+            }
+        }
+        summary.asns_removed = self
+            .as_map
+            .keys()
+            .filter(|asn| !new_as_map.contains_key(asn))
+            .count();
+
+        self.apply_as_updates(new_as_map);
+        self.apply_org_updates(new_org_map);
+        self.dataset_date = parse_dataset_date(path);
+        Ok(summary)
+    }
+
+    /// Replace the AS records with the given ones, updating only the differing records and their
+    /// index entries.
+    #[cfg(feature = "oneio")]
+    fn apply_as_updates(&mut self, new_as_map: FxHashMap<u32, AsRecord>) {
+        let as_map = Arc::make_mut(&mut self.as_map);
+        let as_to_org = Arc::make_mut(&mut self.as_to_org);
+        let org_to_as = Arc::make_mut(&mut self.org_to_as);
+
+        let removed: Vec<u32> = as_map
+            .keys()
+            .filter(|asn| !new_as_map.contains_key(asn))
+            .copied()
+            .collect();
+        for asn in removed {
+            as_map.remove(&asn);
+            if let Some(org_id) = as_to_org.remove(&asn) {
+                remove_from_index(org_to_as, org_id.as_ref(), |a| *a == asn);
+            }
+        }
+
+        for (asn, mut as_entry) in new_as_map {
+            match as_map.get(&asn) {
+                Some(old_entry) if *old_entry == as_entry => continue,
+                Some(old_entry) if old_entry.org_id == as_entry.org_id => {
+                    as_entry.org_id = old_entry.org_id.clone();
+                    as_map.insert(asn, as_entry);
+                    continue;
+                }
+                Some(_) => {
+                    if let Some(org_id) = as_to_org.remove(&asn) {
+                        remove_from_index(org_to_as, org_id.as_ref(), |a| *a == asn);
+                    }
+                }
+                None => {}
+            }
+            as_entry.org_id = interned_key(org_to_as, &as_entry.org_id);
+            as_to_org.insert(asn, as_entry.org_id.clone());
+            org_to_as
+                .entry(as_entry.org_id.clone())
+                .or_default()
+                .push(asn);
+            as_map.insert(asn, as_entry);
+        }
+    }
+
+    /// Replace the organization records with the given ones, updating only the differing records
+    /// and their index entries.
+    #[cfg(feature = "oneio")]
+    fn apply_org_updates(&mut self, new_org_map: FxHashMap<Arc<str>, OrgRecord>) {
+        let org_map = Arc::make_mut(&mut self.org_map);
+        let country_to_orgs = Arc::make_mut(&mut self.country_to_orgs);
+        let source_to_orgs = Arc::make_mut(&mut self.source_to_orgs);
+
+        let removed: Vec<Arc<str>> = org_map
+            .keys()
+            .filter(|org_id| !new_org_map.contains_key(*org_id))
+            .cloned()
+            .collect();
+        for org_id in removed {
+            if let Some(old_entry) = org_map.remove(&org_id) {
+                remove_org_from_indexes(country_to_orgs, source_to_orgs, &old_entry);
+            }
+        }
+
+        for (org_id, mut org_entry) in new_org_map {
+            if let Some(old_entry) = org_map.get(&org_id) {
+                if *old_entry == org_entry {
+                    continue;
+                }
+                remove_org_from_indexes(country_to_orgs, source_to_orgs, old_entry);
+            }
+            org_entry.org_id = interned_key(&self.org_to_as, &org_id);
+            org_entry.country = interned_key(country_to_orgs, &org_entry.country);
+            country_to_orgs
+                .entry(org_entry.country.clone())
+                .or_default()
+                .push(org_entry.org_id.clone());
+            let source = source_key(&org_entry.source);
+            org_entry.source = interned_key(source_to_orgs, &org_entry.source);
+            source_to_orgs
+                .entry(interned_key(source_to_orgs, &source))
+                .or_default()
+                .push(org_entry.org_id.clone());
+            org_map.insert(org_entry.org_id.clone(), org_entry);
+        }
+    }
+
+    /// Compare this dataset against a newer one.
+    ///
+    /// All lists in the returned [As2orgDiff] are sorted by ASN or `org_id`.
+    pub fn diff(&self, other: &As2org) -> As2orgDiff {
+        let mut asns_added: Vec<u32> = other
+            .as_map
+            .keys()
+            .filter(|asn| !self.as_map.contains_key(asn))
+            .copied()
+            .collect();
+        asns_added.sort();
+        let mut asns_removed: Vec<u32> = self
+            .as_map
+            .keys()
+            .filter(|asn| !other.as_map.contains_key(asn))
+            .copied()
+            .collect();
+        asns_removed.sort();
+
+        let asns_changed = self.org_changes(other);
+
+        let mut orgs_added: Vec<String> = other
+            .org_map
+            .keys()
+            .filter(|org_id| !self.org_map.contains_key(*org_id))
+            .map(|org_id| org_id.to_string())
+            .collect();
+        orgs_added.sort();
+        let mut orgs_removed: Vec<String> = self
+            .org_map
+            .keys()
+            .filter(|org_id| !other.org_map.contains_key(*org_id))
+            .map(|org_id| org_id.to_string())
+            .collect();
+        orgs_removed.sort();
+
+        As2orgDiff {
+            asns_added,
+            asns_removed,
+            asns_changed,
+            orgs_added,
+            orgs_removed,
+        }
+    }
+
+    /// Get the ASNs present in both this dataset and a newer one whose organization changed,
+    /// sorted by ASN.
+    ///
+    /// This is a useful signal for detecting acquisitions and re-delegations.
+    pub fn org_changes(&self, other: &As2org) -> Vec<AsnOrgChange> {
+        let org_name = |as2org: &As2org, org_id: &str| -> String {
+            as2org
+                .org_map
+                .get(org_id)
+                .map(|org_entry| org_entry.name.clone())
+                .unwrap_or_default()
+        };
+        let mut res: Vec<AsnOrgChange> = self
+            .as_to_org
+            .iter()
+            .filter_map(|(asn, old_org_id)| {
+                let new_org_id = other.as_to_org.get(asn)?;
+                match old_org_id != new_org_id {
+                    true => Some(AsnOrgChange {
+                        asn: *asn,
+                        old_org_id: old_org_id.to_string(),
+                        old_org_name: org_name(self, old_org_id),
+                        new_org_id: new_org_id.to_string(),
+                        new_org_name: org_name(other, new_org_id),
+                    }),
+                    false => None,
+                }
+            })
+            .collect();
+        res.sort_by_key(|change| change.asn);
+        res
+    }
+
+    /// Get the ASNs of the given organization, sorted in ascending order.
+    ///
+    /// Returns `None` if the organization has no ASes in the dataset.
+    pub fn get_org_asns(&self, org_id: &str) -> Option<Vec<u32>> {
+        let mut asns = self.org_to_as.get(org_id)?.clone();
+        asns.sort();
+        Some(asns)
+    }
+}
+
+/// Stream the raw entries of an AS2Org data file to a callback, without building the lookup maps.
+///
+/// This keeps memory usage low when only a subset of the records is of interest, e.g. the
+/// organizations of a single country:
+///
+/// ```no_run
+/// use as2org_rs::{for_each_entry, As2orgEntry};
+///
+/// let mut orgs = vec![];
+/// for_each_entry("20240701.as-org2info.jsonl.gz", |entry| {
+///     if let As2orgEntry::Org(org) = entry {
+///         if org.country() == "NZ" {
+///             orgs.push(org);
+///         }
+///     }
+/// })
+/// .unwrap();
+/// ```
+#[cfg(feature = "oneio")]
+pub fn for_each_entry(path: &str, mut f: impl FnMut(As2orgEntry)) -> Result<()> {
+    let mut lines = read_data_lines(path)?.enumerate().peekable();
+    if let Some(first_line) = skip_to_array_start(&mut lines) {
+        // the older JSON array format can only be parsed as a whole
+        let entries = parse_as2org_array(lines.map(|(_, line)| line), first_line, true)?;
+        entries.into_iter().for_each(f);
+        return Ok(());
+    }
+    for (index, line) in lines {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry = parse_as2org_line(line.as_str(), true).map_err(|e| As2orgError::Parse {
+            line: index + 1,
+            source: e,
+        })?;
+        f(entry);
+    }
+    Ok(())
+}
+
+/// Options for parsing AS2Org JSONL lines.
+#[derive(Debug, Clone, Copy)]
+struct ParseOptions {
+    /// skip lines that fail to parse and report them as [ParseError]s instead of aborting
+    lenient: bool,
+    /// repair mis-encoded names with [fix_latin1_misinterpretation]
+    fix_encoding: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            lenient: false,
+            fix_encoding: true,
+        }
+    }
+}
+
+#[cfg(feature = "oneio")]
+impl ParseOptions {
+    fn lenient() -> Self {
+        Self {
+            lenient: true,
+            ..Self::default()
+        }
+    }
+}
+
+/// parse remote AS2Org file into Vec of DataEntry
+#[cfg(feature = "oneio")]
+fn parse_as2org_file(
+    path: &str,
+    options: ParseOptions,
+) -> Result<(Vec<As2orgEntry>, Vec<ParseError>)> {
+    let res = parse_as2org_lines(read_data_lines(path)?, options);
+    ensure_records(path, res, |(entries, _)| !entries.is_empty())
+}
+
+/// Check that a data file contains any records, given the result of loading it.
+///
+/// Errors are passed through unchanged, so [As2orgError::NoRecords] is only returned if the file
+/// was parsed completely without producing any records, e.g. because it is empty or all of its
+/// lines were skipped in lenient mode.
+#[cfg(feature = "oneio")]
+fn ensure_records<T>(
+    path: &str,
+    res: Result<T>,
+    has_records: impl FnOnce(&T) -> bool,
+) -> Result<T> {
+    match res {
+        Ok(res) if !has_records(&res) => Err(As2orgError::NoRecords(path.to_string())),
+        res => res,
+    }
+}
+
+/// read the lines of a local or remote data file, decompressing it based on the file extension
+///
+/// `oneio` handles the common compressions, while zstd (`.zst`) is decoded here.
+#[cfg(feature = "oneio")]
+fn read_data_lines(path: &str) -> Result<std::io::Lines<Box<dyn BufRead + Send>>> {
+    let reader = oneio::get_reader(path)?;
+    let reader: Box<dyn BufRead + Send> = match path.ends_with(".zst") {
+        true => Box::new(BufReader::new(zstd::Decoder::new(reader)?)),
+        false => Box::new(BufReader::new(reader)),
+    };
+    Ok(reader.lines())
+}
+
+/// parse AS2Org JSONL lines into Vec of DataEntry
+///
+/// In lenient mode, lines that fail to parse are skipped and reported as [ParseError]s instead of
+/// aborting the parsing.
+///
+/// If the first non-empty line starts a JSON array, the lines are parsed as a whole in the older JSON
+/// array format instead. Lenient mode does not apply to this format.
+fn parse_as2org_lines<I>(
+    lines: I,
+    options: ParseOptions,
+) -> Result<(Vec<As2orgEntry>, Vec<ParseError>)>
+where
+    I: Iterator<Item = std::io::Result<String>>,
+{
+    let mut res: Vec<As2orgEntry> = vec![];
+    let mut errors: Vec<ParseError> = vec![];
+
+    let mut handle_parsed = |index: usize, parsed: serde_json::Result<As2orgEntry>| -> Result<()> {
+        match parsed {
+            Ok(entry) => res.push(entry),
+            Err(e) if options.lenient => {
+                #[cfg(feature = "logging")]
+                log::warn!("skipping line {}: {e}", index + 1);
+                errors.push(ParseError {
+                    line: index + 1,
+                    message: e.to_string(),
+                });
+            }
+            Err(e) => {
+                return Err(As2orgError::Parse {
+                    line: index + 1,
+                    source: e,
+                });
+            }
+        }
+        Ok(())
+    };
+
+    let mut lines = lines.enumerate().peekable();
+    if let Some(first_line) = skip_to_array_start(&mut lines) {
+        let lines = lines.map(|(_, line)| line);
+        let entries = parse_as2org_array(lines, first_line, options.fix_encoding)?;
+        return Ok((entries, vec![]));
+    }
+
+    #[cfg(not(feature = "rayon"))]
+    for (index, line) in lines {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let parsed = parse_as2org_line(line.as_str(), options.fix_encoding);
+        handle_parsed(index, parsed)?;
+    }
+
+    // with rayon, read all lines first and deserialize them in parallel, then handle the results
+    // in input order so that the loaded data does not depend on the thread scheduling
+    #[cfg(feature = "rayon")]
+    {
+        use rayon::prelude::*;
+
+        let lines: Vec<(usize, String)> = lines
+            .map(|(index, line)| Ok((index, line?)))
+            .collect::<std::io::Result<Vec<(usize, String)>>>()?;
+        let parsed: Vec<(usize, serde_json::Result<As2orgEntry>)> = lines
+            .par_iter()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| (*index, parse_as2org_line(line, options.fix_encoding)))
+            .collect();
+        for (index, parsed) in parsed {
+            handle_parsed(index, parsed)?;
+        }
+    }
+
+    Ok((res, errors))
+}
+
+/// parse a single AS2Org JSONL line
+///
+/// If `fix_encoding` is set, mis-encoded AS and organization names are repaired with
+/// [fix_latin1_misinterpretation].
+fn parse_as2org_line(line: &str, fix_encoding: bool) -> serde_json::Result<As2orgEntry> {
+    let mut entry = serde_json::from_str::<As2orgEntry>(line)?;
+    if fix_encoding {
+        fix_entry_encoding(&mut entry);
+    }
+    Ok(entry)
+}
+
+/// skip the leading empty lines, returning the index of the first non-empty line if it starts the
+/// older JSON array format
+///
+/// The line must start with `[` followed by the first object `{`, the closing `]` or the end of the
+/// line, so that a corrupt JSONL record starting with `[` is still reported as such.
+fn skip_to_array_start<I>(lines: &mut std::iter::Peekable<std::iter::Enumerate<I>>) -> Option<usize>
+where
+    I: Iterator<Item = std::io::Result<String>>,
+{
+    while let Some((_, Ok(line))) = lines.peek() {
+        if !line.trim().is_empty() {
+            break;
+        }
+        lines.next();
+    }
+    match lines.peek() {
+        Some((index, Ok(line))) => {
+            let rest = line.trim_start().strip_prefix('[')?;
+            matches!(rest.trim_start().chars().next(), None | Some('{' | ']')).then_some(*index)
+        }
+        _ => None,
+    }
+}
+
+/// parse the entries of the older JSON array format, given the lines of the whole document
+///
+/// `first_line` is the 0-based index of the first given line, for reporting the line of errors.
+fn parse_as2org_array<I>(
+    lines: I,
+    first_line: usize,
+    fix_encoding: bool,
+) -> Result<Vec<As2orgEntry>>
+where
+    I: Iterator<Item = std::io::Result<String>>,
+{
+    let mut content = String::new();
+    for line in lines {
+        content.push_str(line?.as_str());
+        content.push('\n');
+    }
+    let mut entries = serde_json::from_str::<Vec<As2orgEntry>>(content.as_str()).map_err(|e| {
+        As2orgError::Parse {
+            line: first_line + e.line(),
+            source: e,
+        }
+    })?;
+    if fix_encoding {
+        entries.iter_mut().for_each(fix_entry_encoding);
+    }
+    Ok(entries)
+}
+
+/// repair the mis-encoded name of an entry with [fix_latin1_misinterpretation]
+fn fix_entry_encoding(entry: &mut As2orgEntry) {
+    let name = match entry {
+        As2orgEntry::Org(org_entry) => &mut org_entry.name,
+        As2orgEntry::As(as_entry) => &mut as_entry.name,
+    };
+    if let Cow::Owned(fixed) = fix_latin1_misinterpretation(name) {
+        *name = fixed;
+    }
+}
+
+/// Check whether the `changed` date of a record is strictly newer than that of another record.
+///
+/// Returns `false` if either date is missing.
+fn is_newer(changed: Option<&str>, other_changed: Option<&str>) -> bool {
+    match (
+        parse_changed_date(changed),
+        parse_changed_date(other_changed),
+    ) {
+        (Some(changed), Some(other_changed)) => changed > other_changed,
+        _ => false,
+    }
+}
+
+/// Parse a `changed` date field in the `YYYYMMDD` format used by CAIDA.
+///
+/// Empty or malformed values are treated as missing.
+fn parse_changed_date(changed: Option<&str>) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(changed?.trim(), "%Y%m%d").ok()
+}
+
+/// Parse the dataset date from a CAIDA as-org2info file name or URL, e.g. `2024-07-01` for
+/// `20240701.as-org2info.jsonl.gz`.
+///
+/// Returns `None` if the path does not contain a dated as-org2info file name.
+///
+/// ```rust
+/// use as2org_rs::parse_dataset_date;
+/// use chrono::NaiveDate;
+///
+/// assert_eq!(
+///     parse_dataset_date("/data/20240701.as-org2info.jsonl.gz"),
+///     NaiveDate::from_ymd_opt(2024, 7, 1)
+/// );
+/// assert_eq!(parse_dataset_date("latest.as-org2info.jsonl.gz"), None);
+/// ```
+pub fn parse_dataset_date(path: &str) -> Option<NaiveDate> {
+    let cap = DATASET_DATE_REGEX.captures(path)?;
+    NaiveDate::parse_from_str(&cap[1], "%Y%m%d").ok()
+}
+
+/// Combine an AS entry with its organization entry, if present.
+fn build_as_info(as_entry: &AsRecord, org_entry: Option<&OrgRecord>) -> As2orgAsInfo {
+    As2orgAsInfo {
+        asn: as_entry.asn,
+        name: as_entry.name.clone(),
+        country_code: org_entry.map(|o| o.country.to_string()).unwrap_or_default(),
+        org_id: as_entry.org_id.to_string(),
+        org_name: org_entry.map(|o| o.name.clone()).unwrap_or_default(),
+        source: org_entry.map_or_else(|| as_entry.source.to_string(), |o| o.source.to_string()),
+        data_type: AS_DATA_TYPE.to_string(),
+        opaque_id: as_entry.opaque_id.clone(),
+        as_changed: parse_changed_date(as_entry.changed.as_deref()),
+        org_changed: org_entry.and_then(|o| parse_changed_date(o.changed.as_deref())),
+    }
+}
+
+/// Normalize an organization name for comparison by lowercasing it and collapsing whitespace.
+fn normalize_org_name(name: &str) -> String {
+    name.split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Compute the Levenshtein edit distance between two character sequences.
+fn levenshtein(a: &[char], b: &[char]) -> usize {
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut cur = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        cur[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = prev[j] + usize::from(ca != cb);
+            cur[j + 1] = substitution.min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut cur);
+    }
+    prev[b.len()]
+}
+
+/// Quote a CSV field if it contains commas, quotes or line breaks.
+fn csv_field(value: &str) -> Cow<'_, str> {
+    match value.contains([',', '"', '\n', '\r']) {
+        true => Cow::Owned(format!("\"{}\"", value.replace('"', "\"\""))),
+        false => Cow::Borrowed(value),
+    }
+}
+
+/// Find the cached data file with the most recent dataset date in the cache directory.
+#[cfg(feature = "oneio")]
+fn find_cached_file(cache_dir: &str) -> Option<PathBuf> {
+    std::fs::read_dir(cache_dir)
+        .ok()?
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let file_name = path.file_name()?.to_str()?;
+            if !file_name.ends_with(".gz") && !file_name.ends_with(".zst") {
+                return None;
+            }
+            let date = parse_dataset_date(file_name)?;
+            Some((path, date))
+        })
+        .max_by_key(|(_, date)| *date)
+        .map(|(path, _)| path)
+}
+
+/// Download the most recent data file listed at the base URL into the cache directory, returning
+/// the path of the cached file.
+///
+/// If the file is already cached, it is not downloaded again, but its modification time is
+/// refreshed.
+#[cfg(feature = "oneio")]
+fn download_latest_to_cache(cache_dir: &str, base_url: &str) -> Result<PathBuf> {
+    let url = get_most_recent_data(base_url)?;
+    download_to_cache(cache_dir, url.as_str(), |url, tmp_path| {
+        oneio::download(url, tmp_path.to_string_lossy().as_ref(), None)?;
+        Ok(())
+    })
+}
+
+/// Download the data file at the given URL into the cache directory with the given download
+/// function, returning the path of the cached file.
+///
+/// If the file is already cached, it is not downloaded again, but its modification time is
+/// refreshed.
+#[cfg(feature = "oneio")]
+fn download_to_cache(
+    cache_dir: &str,
+    url: &str,
+    download: impl FnOnce(&str, &Path) -> Result<()>,
+) -> Result<PathBuf> {
+    let file_name = url.rsplit('/').next().unwrap_or(url);
+    let path = Path::new(cache_dir).join(file_name);
+    if path.exists() {
+        std::fs::File::options()
+            .append(true)
+            .open(&path)?
+            .set_modified(SystemTime::now())?;
+        return Ok(path);
+    }
+
+    // download to a temporary file first to avoid leaving partial files in the cache
+    let tmp_path = Path::new(cache_dir).join(format!("{file_name}.tmp"));
+    if let Err(e) = download(url, tmp_path.as_path()) {
+        let _ = std::fs::remove_file(&tmp_path);
+        return Err(e);
+    }
+    std::fs::rename(&tmp_path, &path)?;
+    Ok(path)
+}
+
+/// Wrap a raw data stream in a decompressing reader based on the file extension of the given
+/// path, supporting the same formats as `oneio` plus zstd.
+#[cfg(feature = "oneio")]
+fn decompress_reader(
+    path: &str,
+    raw: impl Read + Send + 'static,
+) -> Result<Box<dyn BufRead + Send>> {
+    let reader: Box<dyn Read + Send> = match path.rsplit('.').next().unwrap_or_default() {
+        "gz" | "gzip" | "tgz" => Box::new(GzDecoder::new(raw)),
+        "bz2" | "bz" => Box::new(bzip2::read::BzDecoder::new(raw)),
+        "lz4" | "lz" => Box::new(lz4::Decoder::new(raw)?),
+        "xz" | "xz2" | "lzma" => Box::new(xz2::read::XzDecoder::new(raw)),
+        "zst" => Box::new(zstd::Decoder::new(raw)?),
+        _ => Box::new(raw),
+    };
+    Ok(Box::new(BufReader::new(reader)))
+}
+
+/// Set of shared string copies, so that repeated strings of a dataset are stored only once.
+#[derive(Default)]
+struct Interner(FxHashSet<Arc<str>>);
+
+impl Interner {
+    /// Get the shared copy of the given string, adding it if it is not interned yet.
+    fn intern(&mut self, s: &str) -> Arc<str> {
+        if let Some(interned) = self.0.get(s) {
+            return interned.clone();
+        }
+        let interned: Arc<str> = Arc::from(s);
+        self.0.insert(interned.clone());
+        interned
+    }
+}
+
+/// Get the key of the given source in the source index, which is case-insensitive.
+fn source_key(source: &Arc<str>) -> Arc<str> {
+    match source.chars().any(char::is_lowercase) {
+        true => Arc::from(source.to_uppercase()),
+        false => source.clone(),
+    }
+}
+
+/// Collect the AS and organization records of the given entries by ASN and `org_id`, keeping the
+/// more recently changed record of duplicates.
+///
+/// The `org_id`, country and source fields of the kept records share one copy of each distinct
+/// value.
+fn collect_records(
+    entries: Vec<As2orgEntry>,
+) -> (FxHashMap<u32, AsRecord>, FxHashMap<Arc<str>, OrgRecord>) {
+    let mut as_map: FxHashMap<u32, AsRecord> = FxHashMap::default();
+    let mut org_map: FxHashMap<Arc<str>, OrgRecord> = FxHashMap::default();
+    let mut interner = Interner::default();
+
+    for entry in entries {
+        match entry {
+            As2orgEntry::As(mut as_entry) => {
+                // keep the more recently changed record of duplicate ASNs
+                if let Some(existing) = as_map.get(&as_entry.asn) {
+                    if is_newer(existing.changed.as_deref(), as_entry.changed.as_deref()) {
+                        continue;
+                    }
+                }
+                as_entry.org_id = interner.intern(&as_entry.org_id);
+                as_entry.source = interner.intern(&as_entry.source);
+                as_map.insert(as_entry.asn, as_entry);
+            }
+            As2orgEntry::Org(mut org_entry) => {
+                // keep the more recently changed record of duplicate organizations
+                if let Some(existing) = org_map.get(&org_entry.org_id) {
+                    if is_newer(existing.changed.as_deref(), org_entry.changed.as_deref()) {
+                        continue;
+                    }
+                }
+                org_entry.org_id = interner.intern(&org_entry.org_id);
+                org_entry.country = interner.intern(&org_entry.country.trim().to_uppercase());
+                org_entry.source = interner.intern(&org_entry.source);
+                org_map.insert(org_entry.org_id.clone(), org_entry);
+            }
+        }
+    }
+    (as_map, org_map)
+}
+
+/// Get the copy of the given string that is a key of the index, so that updated records share it
+/// with the existing ones, or the given string itself if it is not a key.
+#[cfg(feature = "oneio")]
+fn interned_key<V>(index: &FxHashMap<Arc<str>, V>, s: &Arc<str>) -> Arc<str> {
+    match index.get_key_value(s.as_ref()) {
+        Some((interned, _)) => interned.clone(),
+        None => s.clone(),
+    }
+}
+
+/// Remove an organization from the country and source indexes.
+#[cfg(feature = "oneio")]
+fn remove_org_from_indexes(
+    country_to_orgs: &mut FxHashMap<Arc<str>, Vec<Arc<str>>>,
+    source_to_orgs: &mut FxHashMap<Arc<str>, Vec<Arc<str>>>,
+    org_entry: &OrgRecord,
+) {
+    let is_org = |org_id: &Arc<str>| *org_id == org_entry.org_id;
+    remove_from_index(country_to_orgs, org_entry.country.as_ref(), is_org);
+    remove_from_index(
+        source_to_orgs,
+        source_key(&org_entry.source).as_ref(),
+        is_org,
+    );
+}
+
+/// Remove the values matching the predicate from the index entry of the given key, dropping the
+/// entry once it is empty.
+#[cfg(feature = "oneio")]
+fn remove_from_index<K, Q, V>(index: &mut FxHashMap<K, Vec<V>>, key: &Q, f: impl Fn(&V) -> bool)
+where
+    K: std::borrow::Borrow<Q> + std::hash::Hash + Eq,
+    Q: std::hash::Hash + Eq + ?Sized,
+{
+    if let Some(values) = index.get_mut(key) {
+        values.retain(|v| !f(v));
+        if values.is_empty() {
+            index.remove(key);
+        }
+    }
+}
+
+/// Parse a data file streamed from the given reader, decompressing it based on the file extension
+/// of the given path.
+#[cfg(feature = "oneio")]
+fn parse_as2org_reader(
+    path: &str,
+    raw: impl Read + Send + 'static,
+    options: ParseOptions,
+) -> Result<(Vec<As2orgEntry>, Vec<ParseError>)> {
+    let reader = decompress_reader(path, raw)?;
+    let res = parse_as2org_lines(reader.lines(), options);
+    ensure_records(path, res, |(entries, _)| !entries.is_empty())
+}
+
+/// Parse the data files linked from a dataset index page, sorted by date and then URL in
+/// ascending order.
+#[cfg(feature = "oneio")]
+fn parse_file_listing(content: &str, base_url: &str) -> Vec<DataFile> {
+    let mut res: Vec<DataFile> = DATA_LINK_REGEX
+        .captures_iter(content)
+        .filter_map(|cap| {
+            let file = &cap[1];
+            let date = parse_dataset_date(file)?;
+            Some((format!("{base_url}{file}"), date))
+        })
+        .collect();
+    res.sort_by(|(url_a, date_a), (url_b, date_b)| (date_a, url_a).cmp(&(date_b, url_b)));
+    res.dedup();
+    res
+}
+
+/// Get the most recent AS2Org data file listed at the given base URL
+#[cfg(feature = "oneio")]
+fn get_most_recent_data(base_url: &str) -> Result<String> {
+    let files = As2org::get_all_files_with_dates_from(base_url)?;
+    match files.last() {
+        Some((url, _date)) => Ok(url.clone()),
+        None => Err(As2orgError::NoDatasets(base_url.to_string())),
+    }
+}
+
+/// A data file URL along with its dataset date.
+#[cfg(feature = "oneio")]
+type DataFile = (String, NaiveDate);
+
+/// Keep the files with dates between `start` and `end` (both inclusive).
+#[cfg(feature = "oneio")]
+fn filter_files_between(files: Vec<DataFile>, start: NaiveDate, end: NaiveDate) -> Vec<DataFile> {
+    files
+        .into_iter()
+        .filter(|(_, d)| *d >= start && *d <= end)
+        .collect()
+}
+
+/// Find the closest files strictly before and strictly after the given date.
+///
+/// `files` must be sorted by date in ascending order.
+#[cfg(feature = "oneio")]
+fn find_adjacent_files(
+    files: &[DataFile],
+    date: NaiveDate,
+) -> (Option<&DataFile>, Option<&DataFile>) {
+    let before = files.iter().rev().find(|(_, d)| *d < date);
+    let after = files.iter().find(|(_, d)| *d > date);
+    (before, after)
+}
+
+/// Find the file published on the given date, returning an error listing the nearest available
+/// dates if there is none.
+///
+/// `files` must be sorted by date in ascending order.
+#[cfg(feature = "oneio")]
+fn find_file_on_date(files: &[DataFile], date: NaiveDate) -> Result<&DataFile> {
+    if let Some(file) = files.iter().find(|(_, d)| *d == date) {
+        return Ok(file);
+    }
+    let (before, after) = find_adjacent_files(files, date);
+    let nearby: Vec<NaiveDate> = [before, after]
+        .into_iter()
+        .flatten()
+        .map(|(_, d)| *d)
+        .collect();
+    Err(As2orgError::DateNotFound { date, nearby })
+}
+
+/// Find the file with the date closest to the given date, preferring the earlier file on ties.
+///
+/// `files` must be sorted by date in ascending order.
+#[cfg(feature = "oneio")]
+fn find_nearest_file(files: &[DataFile], date: NaiveDate) -> Option<&DataFile> {
+    if let Some(file) = files.iter().find(|(_, d)| *d == date) {
+        return Some(file);
+    }
+    match find_adjacent_files(files, date) {
+        (Some(before), Some(after)) => match (date - before.1) <= (after.1 - date) {
+            true => Some(before),
+            false => Some(after),
+        },
+        (before, after) => before.or(after),
+    }
+}
+
+#[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
-    fn test_load_entries() {
-        let as2org = As2org::new(None).unwrap();
-        dbg!(as2org.get_as_info(400644));
-        dbg!(as2org.get_siblings(400644));
-        dbg!(as2org.get_siblings(13335));
+    #[cfg(feature = "oneio")]
+    #[ignore = "downloads the latest dataset from CAIDA"]
+    fn test_load_latest() {
+        let as2org = As2org::new(None).unwrap();
+        assert!(as2org.get_as_info(400644).is_some());
+        assert!(as2org.are_siblings(15169, 36040));
+        assert!(as2org.dataset_date().is_some());
+    }
+
+    /// Path of a small data file covering a multi-AS organization, a mis-encoded organization
+    /// name and an AS whose organization is missing.
+    #[cfg(feature = "oneio")]
+    pub(crate) const FIXTURE_PATH: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/20240701.as-org2info.jsonl"
+    );
+    const FIXTURE: &str = include_str!("../tests/fixtures/20240701.as-org2info.jsonl");
+    /// The same data as [FIXTURE] in the older JSON array format.
+    const FIXTURE_ARRAY: &str = include_str!("../tests/fixtures/20240701.as-org2info.json");
+
+    /// Temporary directory for the data files of a test, removed with its content when dropped.
+    #[cfg(feature = "oneio")]
+    pub(crate) struct TempDir(PathBuf);
+
+    #[cfg(feature = "oneio")]
+    impl TempDir {
+        /// Create an empty directory named after the test, unique to the test process.
+        pub(crate) fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("as2org-{name}-{}", std::process::id()));
+            let _ = std::fs::remove_dir_all(&dir);
+            std::fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+
+        /// Get the path of the directory.
+        pub(crate) fn path(&self) -> &str {
+            self.0.to_str().unwrap()
+        }
+
+        /// Get the path of the given file in the directory.
+        pub(crate) fn file(&self, file_name: &str) -> String {
+            self.0.join(file_name).to_string_lossy().to_string()
+        }
+
+        /// Write a file into the directory, returning its path.
+        pub(crate) fn write(&self, file_name: &str, content: impl AsRef<[u8]>) -> String {
+            let path = self.file(file_name);
+            std::fs::write(&path, content).unwrap();
+            path
+        }
+    }
+
+    #[cfg(feature = "oneio")]
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn test_fixture() {
+        let as2org = As2org::from_jsonl_str(FIXTURE).unwrap();
+        assert_eq!(as2org.as_count(), 10);
+        assert_eq!(as2org.org_count(), 6);
+
+        let siblings: Vec<u32> = as2org
+            .get_siblings(36040)
+            .unwrap()
+            .iter()
+            .map(|info| info.asn)
+            .collect();
+        assert_eq!(siblings, vec![15169, 36040, 396982]);
+        assert!(as2org.are_siblings(3215, 5511));
+        assert!(!as2org.are_siblings(15169, 13335));
+
+        assert_eq!(
+            as2org.get_org_info("ORG-FT2-RIPE").unwrap().name,
+            "Orangé S.A."
+        );
+        assert_eq!(as2org.get_as_info(4134).unwrap().country_code, "CN");
+        assert_eq!(
+            as2org.get_as_info(4134).unwrap().opaque_id.as_deref(),
+            Some("A91A7381")
+        );
+        assert!(as2org.get_as_info(64512).is_none());
+        assert_eq!(as2org.orphan_asns(), vec![64512]);
+    }
+
+    #[test]
+    #[cfg(feature = "oneio")]
+    fn test_load_fixture_file() {
+        let as2org = As2org::new(Some(FIXTURE_PATH.to_string())).unwrap();
+        assert_eq!(as2org.dataset_date(), NaiveDate::from_ymd_opt(2024, 7, 1));
+        assert_eq!(as2org.as_count(), 10);
+        assert_eq!(as2org.get_as_info(400644).unwrap().org_name, "BGPKIT LLC");
+        assert_eq!(
+            as2org.get_org_info("ORG-FT2-RIPE").unwrap().name,
+            "Orangé S.A."
+        );
+    }
+
+    const TEST_DATA: &str = r#"{"changed":"20240701","name":"Google LLC","country":"US","organizationId":"GOGL-ARIN","source":"ARIN","type":"Organization"}
+{"changed":"20240701","asn":"15169","name":"GOOGLE","organizationId":"GOGL-ARIN","opaqueId":"","source":"ARIN","type":"ASN"}
+{"changed":"20240701","asn":"36040","name":"YOUTUBE","organizationId":"GOGL-ARIN","opaqueId":"","source":"ARIN","type":"ASN"}
+{"changed":"20240625","name":"Cloudflare, Inc.","country":"US","organizationId":"CLOUD14-ARIN","source":"ARIN","type":"Organization"}
+{"changed":"20240625","asn":"13335","name":"CLOUDFLARENET","organizationId":"CLOUD14-ARIN","opaqueId":"","source":"ARIN","type":"ASN"}
+{"changed":"20240601","name":"Deutsche Telekom AG","country":"DE","organizationId":"ORG-DTAG1-RIPE","source":"RIPE","type":"Organization"}
+{"changed":"20240601","asn":"3320","name":"DTAG","organizationId":"ORG-DTAG1-RIPE","opaqueId":"","source":"RIPE","type":"ASN"}
+"#;
+
+    #[test]
+    fn test_from_jsonl_str() {
+        let as2org = As2org::from_jsonl_str(TEST_DATA).unwrap();
+        assert_eq!(as2org.as_count(), 4);
+        assert_eq!(as2org.org_count(), 3);
+        assert!(!as2org.is_empty());
+        assert!(as2org.contains_asn(15169));
+        assert!(!as2org.contains_asn(64512));
+        assert!(as2org.contains_org("GOGL-ARIN"));
+        assert!(!as2org.contains_org("MISSING-ARIN"));
+        assert_eq!(
+            as2org.get_as_info(13335).unwrap().org_name,
+            "Cloudflare, Inc."
+        );
+        assert!(as2org.are_siblings(15169, 36040));
+        assert!(!as2org.are_siblings(15169, 13335));
+        let info = as2org.get_as_info(15169).unwrap();
+        assert_eq!(info.as_changed, NaiveDate::from_ymd_opt(2024, 7, 1));
+        assert_eq!(info.org_changed, NaiveDate::from_ymd_opt(2024, 7, 1));
+        assert_eq!(as2org.dataset_date(), None);
+
+        assert!(As2org::from_jsonl_str("not json").is_err());
+        assert!(As2org::from_jsonl_str("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_from_reader() {
+        let reader = std::io::Cursor::new(TEST_DATA.as_bytes());
+        let as2org = As2org::from_reader(reader).unwrap();
+        assert_eq!(as2org.get_siblings(15169).unwrap().len(), 2);
+    }
+
+    #[test]
+    #[cfg(feature = "oneio")]
+    fn test_parse_lenient() {
+        let content = TEST_DATA.replacen("{", "[", 2);
+        let lines = content.lines().map(|line| Ok(line.to_string()));
+        assert!(matches!(
+            parse_as2org_lines(lines, ParseOptions::default()),
+            Err(As2orgError::Parse { line: 1, .. })
+        ));
+
+        let lines = content.lines().map(|line| Ok(line.to_string()));
+        let (entries, errors) = parse_as2org_lines(lines, ParseOptions::lenient()).unwrap();
+        assert_eq!(entries.len(), 5);
+        assert_eq!(
+            errors.iter().map(|e| e.line).collect::<Vec<usize>>(),
+            vec![1, 2]
+        );
+    }
+
+    #[test]
+    fn test_siblings_with_missing_org() {
+        let entries = vec![
+            As2orgEntry::Org(
+                serde_json::from_str(r#"{"changed":"20240701","name":"Google LLC","country":"US","organizationId":"GOGL-ARIN","source":"ARIN","type":"Organization"}"#).unwrap(),
+            ),
+            As2orgEntry::As(
+                serde_json::from_str(r#"{"changed":"20240701","asn":"15169","name":"GOOGLE","organizationId":"GOGL-ARIN","opaqueId":"","source":"ARIN","type":"ASN"}"#).unwrap(),
+            ),
+            As2orgEntry::As(
+                serde_json::from_str(r#"{"changed":"20240701","asn":"36040","name":"YOUTUBE","organizationId":"GOGL-ARIN","opaqueId":"","source":"ARIN","type":"ASN"}"#).unwrap(),
+            ),
+            As2orgEntry::As(
+                serde_json::from_str(r#"{"changed":"20240701","asn":"64512","name":"DANGLING","organizationId":"MISSING-ARIN","opaqueId":"","source":"ARIN","type":"ASN"}"#).unwrap(),
+            ),
+        ];
+        let mut as2org = As2org::from_entries(entries, None);
+        // an inconsistent dataset where the dangling AS is grouped with a valid organization
+        Arc::get_mut(&mut as2org.org_to_as)
+            .unwrap()
+            .get_mut("GOGL-ARIN")
+            .unwrap()
+            .push(64512);
+
+        let asns: Vec<u32> = as2org
+            .get_siblings(15169)
+            .unwrap()
+            .iter()
+            .map(|s| s.asn)
+            .collect();
+        assert_eq!(asns, vec![15169, 36040]);
+        assert!(as2org.get_siblings(64512).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_entry_type() {
+        let line = r#"{
+            "type": "ASN",
+            "source": "ARIN",
+            "organizationId": "GOGL-ARIN",
+            "name": "GOOGLE",
+            "asn": "15169",
+            "changed": "20240701"
+        }"#;
+        match parse_as2org_line(line, true).unwrap() {
+            As2orgEntry::As(as_entry) => assert_eq!(as_entry.asn, 15169),
+            As2orgEntry::Org(_) => panic!("expected an AS entry"),
+        }
+
+        let line = r#"{"changed":"20240701","name":"Fake \"type\":\"ASN\" Org","country":"US","organizationId":"FAKE-ARIN","source":"ARIN","type":"Organization"}"#;
+        match parse_as2org_line(line, true).unwrap() {
+            As2orgEntry::Org(org_entry) => {
+                assert_eq!(org_entry.name, r#"Fake "type":"ASN" Org"#)
+            }
+            As2orgEntry::As(_) => panic!("expected an organization entry"),
+        }
+
+        assert!(parse_as2org_line(r#"{"type":"Unknown"}"#, true).is_err());
+    }
+
+    #[test]
+    fn test_invalid_asn() {
+        let line = r#"{"changed":"20240701","asn":"ASN15169","name":"GOOGLE","organizationId":"GOGL-ARIN","opaqueId":"","source":"ARIN","type":"ASN"}"#;
+        let err = serde_json::from_str::<AsRecord>(line).unwrap_err();
+        assert!(err.to_string().contains(r#"invalid ASN "ASN15169""#));
+
+        let line = r#"{"changed":"20240701","asn":"15169","name":"GOOGLE","organizationId":"GOGL-ARIN","opaqueId":"","source":"ARIN","type":"ASN"}"#;
+        let entry = serde_json::from_str::<AsRecord>(line).unwrap();
+        assert_eq!(entry.asn, 15169);
+    }
+
+    #[test]
+    #[cfg(feature = "oneio")]
+    fn test_find_nearest_file() {
+        let date = |d: u32| NaiveDate::from_ymd_opt(2024, 1, d).unwrap();
+        let files = vec![
+            ("a".to_string(), date(1)),
+            ("b".to_string(), date(11)),
+            ("c".to_string(), date(21)),
+        ];
+        assert_eq!(find_nearest_file(&files, date(11)).unwrap().0, "b");
+        assert_eq!(find_nearest_file(&files, date(14)).unwrap().0, "b");
+        assert_eq!(find_nearest_file(&files, date(16)).unwrap().0, "b");
+        assert_eq!(find_nearest_file(&files, date(17)).unwrap().0, "c");
+        assert_eq!(find_nearest_file(&files, date(31)).unwrap().0, "c");
+        assert!(find_nearest_file(&[], date(1)).is_none());
+
+        let (before, after) = find_adjacent_files(&files, date(11));
+        assert_eq!(before.unwrap().0, "a");
+        assert_eq!(after.unwrap().0, "c");
+        let (before, after) = find_adjacent_files(&files, date(1));
+        assert!(before.is_none());
+        assert_eq!(after.unwrap().0, "b");
+        let (before, after) = find_adjacent_files(&files, date(31));
+        assert_eq!(before.unwrap().0, "c");
+        assert!(after.is_none());
+
+        let between = filter_files_between(files.clone(), date(1), date(11));
+        assert_eq!(between, files[..2].to_vec());
+        assert!(filter_files_between(files, date(12), date(20)).is_empty());
+    }
+
+    #[test]
+    fn test_parse_changed_date() {
+        assert_eq!(
+            parse_changed_date(Some("20240701")),
+            NaiveDate::from_ymd_opt(2024, 7, 1)
+        );
+        assert_eq!(parse_changed_date(Some("")), None);
+        assert_eq!(parse_changed_date(Some("2024-07-01")), None);
+        assert_eq!(parse_changed_date(None), None);
+    }
+
+    #[test]
+    fn test_parse_dataset_date() {
+        assert_eq!(
+            parse_dataset_date("https://publicdata.caida.org/datasets/as-organizations/20240701.as-org2info.jsonl.gz"),
+            NaiveDate::from_ymd_opt(2024, 7, 1)
+        );
+        assert_eq!(parse_dataset_date("/tmp/as2org.jsonl"), None);
+        assert_eq!(parse_dataset_date("20241341.as-org2info.jsonl.gz"), None);
+    }
+
+    #[test]
+    fn test_get_asns_by_country() {
+        let as2org = As2org::from_jsonl_str(TEST_DATA).unwrap();
+        let asns: Vec<u32> = as2org
+            .get_asns_by_country("us")
+            .iter()
+            .map(|info| info.asn)
+            .collect();
+        assert_eq!(asns, vec![13335, 15169, 36040]);
+        assert!(as2org.get_asns_by_country("XX").is_empty());
+    }
+
+    #[test]
+    fn test_get_orgs_by_country() {
+        let as2org = As2org::from_jsonl_str(TEST_DATA).unwrap();
+        let orgs: Vec<String> = as2org
+            .get_orgs_by_country("us")
+            .into_iter()
+            .map(|org| org.org_id)
+            .collect();
+        assert_eq!(orgs, vec!["CLOUD14-ARIN", "GOGL-ARIN"]);
+        assert_eq!(as2org.get_orgs_by_country("DE").len(), 1);
+        assert!(as2org.get_orgs_by_country("XX").is_empty());
+    }
+
+    #[test]
+    fn test_search_orgs_by_name() {
+        let as2org = As2org::from_jsonl_str(TEST_DATA).unwrap();
+        let orgs = as2org.search_orgs_by_name("GOOGLE");
+        assert_eq!(orgs.len(), 1);
+        assert_eq!(orgs[0].org_id, "GOGL-ARIN");
+
+        let names: Vec<String> = as2org
+            .search_orgs_by_name("e")
+            .into_iter()
+            .map(|org| org.name)
+            .collect();
+        assert_eq!(
+            names,
+            vec!["Cloudflare, Inc.", "Deutsche Telekom AG", "Google LLC"]
+        );
+        assert!(as2org.search_orgs_by_name("no such org").is_empty());
+    }
+
+    #[test]
+    fn test_find_asns_by_name() {
+        let as2org = As2org::from_jsonl_str(TEST_DATA).unwrap();
+        let asns: Vec<u32> = as2org
+            .find_asns_by_name("o")
+            .iter()
+            .map(|info| info.asn)
+            .collect();
+        assert_eq!(asns, vec![13335, 15169, 36040]);
+        assert_eq!(
+            as2org.find_asns_by_name("youtube")[0].org_name,
+            "Google LLC"
+        );
+        assert!(as2org.find_asns_by_name("google llc").is_empty());
+    }
+
+    #[test]
+    fn test_get_as_info_batch() {
+        let as2org = As2org::from_jsonl_str(TEST_DATA).unwrap();
+        let res = as2org.get_as_info_batch(&[15169, 13335, 64512, 15169]);
+        assert_eq!(res.len(), 2);
+        assert_eq!(res[&15169].name, "GOOGLE");
+        assert_eq!(res[&13335].name, "CLOUDFLARENET");
+        assert!(!res.contains_key(&64512));
+    }
+
+    #[test]
+    fn test_iter_as_info() {
+        let as2org = As2org::from_jsonl_str(TEST_DATA).unwrap();
+        let mut asns: Vec<u32> = as2org.iter_as_info().map(|info| info.asn).collect();
+        asns.sort();
+        assert_eq!(asns, vec![3320, 13335, 15169, 36040]);
+    }
+
+    #[test]
+    fn test_siblings_sorted() {
+        let as2org = As2org::from_jsonl_str(TEST_DATA).unwrap();
+        for asn in [15169, 36040] {
+            let asns: Vec<u32> = as2org
+                .get_siblings(asn)
+                .unwrap()
+                .iter()
+                .map(|s| s.asn)
+                .collect();
+            assert_eq!(asns, vec![15169, 36040]);
+        }
+    }
+
+    #[test]
+    fn test_siblings_excluding_self() {
+        let as2org = As2org::from_jsonl_str(TEST_DATA).unwrap();
+        let siblings = as2org.get_siblings_excluding_self(15169).unwrap();
+        assert_eq!(siblings.len(), 1);
+        assert_eq!(siblings[0].asn, 36040);
+        assert!(as2org
+            .get_siblings_excluding_self(13335)
+            .unwrap()
+            .is_empty());
+        assert!(as2org.get_siblings_excluding_self(64512).is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "oneio")]
+    fn test_cache_roundtrip() {
+        let dir = TempDir::new("cache");
+        let path = dir.file("as2org.cache.json.gz");
+        let path = path.as_str();
+
+        let as2org = As2org::from_jsonl_str(TEST_DATA).unwrap();
+        as2org.save_cache(path).unwrap();
+        let cached = As2org::load_cache(path).unwrap();
+        assert_eq!(cached.as_count(), as2org.as_count());
+        assert_eq!(cached.org_count(), as2org.org_count());
+        assert_eq!(
+            cached.get_as_info(36040).unwrap().org_name,
+            as2org.get_as_info(36040).unwrap().org_name
+        );
+        assert!(cached.are_siblings(15169, 36040));
+
+        let path = dir.write("as2org.cache.v999.json", "{\"version\":999}\n{}\n");
+        assert!(matches!(
+            As2org::load_cache(path.as_str()),
+            Err(As2orgError::InvalidCache(_))
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "oneio")]
+    fn test_parse_file_listing() {
+        let content = r#"<a href="20240401.as-org2info.jsonl.gz">20240401.as-org2info.jsonl.gz</a>
+<a href="20240101.as-org2info.jsonl.zst">20240101.as-org2info.jsonl.zst</a>
+<a href="README.txt">README.txt</a>"#;
+        let files = parse_file_listing(content, BASE_URL);
+        assert_eq!(
+            files,
+            vec![
+                (
+                    format!("{BASE_URL}20240101.as-org2info.jsonl.zst"),
+                    NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()
+                ),
+                (
+                    format!("{BASE_URL}20240401.as-org2info.jsonl.gz"),
+                    NaiveDate::from_ymd_opt(2024, 4, 1).unwrap()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_top_orgs_by_asn_count() {
+        let as2org = As2org::from_jsonl_str(TEST_DATA).unwrap();
+        let top: Vec<(String, usize)> = as2org
+            .top_orgs_by_asn_count(2)
+            .into_iter()
+            .map(|(org, count)| (org.org_id, count))
+            .collect();
+        assert_eq!(
+            top,
+            vec![
+                ("GOGL-ARIN".to_string(), 2),
+                ("CLOUD14-ARIN".to_string(), 1)
+            ]
+        );
+        assert_eq!(as2org.top_orgs_by_asn_count(10).len(), 3);
+    }
+
+    #[test]
+    fn test_diff() {
+        let old = As2org::from_jsonl_str(TEST_DATA).unwrap();
+        let new = As2org::from_jsonl_str(
+            TEST_DATA
+                .replace(
+                    r#""asn":"36040","name":"YOUTUBE","organizationId":"GOGL-ARIN""#,
+                    r#""asn":"36040","name":"YOUTUBE","organizationId":"CLOUD14-ARIN""#,
+                )
+                .replace(r#""asn":"3320""#, r#""asn":"3321""#)
+                .replace("ORG-DTAG1-RIPE", "ORG-DTAG2-RIPE")
+                .as_str(),
+        )
+        .unwrap();
+
+        let diff = old.diff(&new);
+        assert_eq!(diff.asns_added, vec![3321]);
+        assert_eq!(diff.asns_removed, vec![3320]);
+        assert_eq!(
+            diff.asns_changed,
+            vec![AsnOrgChange {
+                asn: 36040,
+                old_org_id: "GOGL-ARIN".to_string(),
+                old_org_name: "Google LLC".to_string(),
+                new_org_id: "CLOUD14-ARIN".to_string(),
+                new_org_name: "Cloudflare, Inc.".to_string(),
+            }]
+        );
+        assert_eq!(diff.orgs_added, vec!["ORG-DTAG2-RIPE"]);
+        assert_eq!(diff.orgs_removed, vec!["ORG-DTAG1-RIPE"]);
+        assert_eq!(old.diff(&old), As2orgDiff::default());
+        assert_eq!(old.org_changes(&new), diff.asns_changed);
+    }
+
+    #[test]
+    fn test_get_org_asns() {
+        let as2org = As2org::from_jsonl_str(TEST_DATA).unwrap();
+        assert_eq!(as2org.get_org_asns("GOGL-ARIN"), Some(vec![15169, 36040]));
+        assert_eq!(as2org.get_org_asns("MISSING-ARIN"), None);
+    }
+
+    #[test]
+    fn test_are_distinct_siblings() {
+        let as2org = As2org::from_jsonl_str(TEST_DATA).unwrap();
+        assert!(as2org.are_siblings(15169, 15169));
+        assert!(!as2org.are_distinct_siblings(15169, 15169));
+        assert!(as2org.are_distinct_siblings(15169, 36040));
+        assert!(!as2org.are_distinct_siblings(15169, 13335));
+    }
+
+    #[test]
+    fn test_source_rir() {
+        assert_eq!(Rir::from("ARIN"), Rir::Arin);
+        assert_eq!(Rir::from("ripe"), Rir::Ripe);
+        assert_eq!(Rir::from("AfriNIC"), Rir::Afrinic);
+        assert_eq!(Rir::from("JPNIC"), Rir::Other("JPNIC".to_string()));
+
+        let as2org = As2org::from_jsonl_str(TEST_DATA).unwrap();
+        assert_eq!(as2org.get_as_info(3320).unwrap().source_rir(), Rir::Ripe);
+    }
+
+    #[test]
+    fn test_get_asns_by_source() {
+        let as2org = As2org::from_jsonl_str(TEST_DATA).unwrap();
+        let asns: Vec<u32> = as2org
+            .get_asns_by_source("arin")
+            .iter()
+            .map(|info| info.asn)
+            .collect();
+        assert_eq!(asns, vec![13335, 15169, 36040]);
+        assert_eq!(as2org.get_asns_by_source("RIPE")[0].asn, 3320);
+        assert!(as2org.get_asns_by_source("LACNIC").is_empty());
+    }
+
+    #[test]
+    fn test_normalize_country() {
+        let content = TEST_DATA
+            .replace(
+                r#""country":"US","organizationId":"GOGL-ARIN""#,
+                r#""country":" us ","organizationId":"GOGL-ARIN""#,
+            )
+            .replace(r#""country":"DE""#, r#""country":"""#);
+        let as2org = As2org::from_jsonl_str(content.as_str()).unwrap();
+
+        let info = as2org.get_as_info(15169).unwrap();
+        assert_eq!(info.country_code, "US");
+        assert!(info.is_valid_country());
+        assert_eq!(info.country_name(), Some("United States"));
+        assert_eq!(as2org.get_org_info("GOGL-ARIN").unwrap().country, "US");
+        assert_eq!(as2org.get_asns_by_country("US").len(), 3);
+
+        let info = as2org.get_as_info(3320).unwrap();
+        assert_eq!(info.country_code, "");
+        assert!(!info.is_valid_country());
+    }
+
+    #[test]
+    #[cfg(feature = "oneio")]
+    fn test_for_each_entry() {
+        let dir = TempDir::new("stream");
+        let path = dir.write("as2org.jsonl", TEST_DATA);
+
+        let mut asns = vec![];
+        let mut org_count = 0;
+        for_each_entry(path.as_str(), |entry| match entry {
+            As2orgEntry::As(as_entry) => asns.push(as_entry.asn),
+            As2orgEntry::Org(_) => org_count += 1,
+        })
+        .unwrap();
+        assert_eq!(asns, vec![15169, 36040, 13335, 3320]);
+        assert_eq!(org_count, 3);
+    }
+
+    /// Serve a single HTTP response with the given body on a local port, returning the base URL.
+    #[cfg(feature = "oneio")]
+    fn serve_once(body: &'static str) -> String {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 4096];
+            let _ = stream.read(&mut buf);
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).unwrap();
+        });
+        format!("http://{addr}/datasets/as-organizations/")
+    }
+
+    #[test]
+    #[cfg(feature = "oneio")]
+    fn test_mirror_without_datasets() {
+        let base_url =
+            serve_once("<html><body><a href=\"README.txt\">README.txt</a></body></html>");
+        match As2org::new_from_mirror(base_url.as_str()) {
+            Err(As2orgError::NoDatasets(url)) => assert_eq!(url, base_url),
+            Err(e) => panic!("unexpected error: {e}"),
+            Ok(_) => panic!("expected an error"),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "oneio")]
+    fn test_load_zstd() {
+        let dir = TempDir::new("zstd");
+        let compressed = zstd::encode_all(TEST_DATA.as_bytes(), 3).unwrap();
+        let path = dir.write("20240701.as-org2info.jsonl.zst", compressed);
+
+        let as2org = As2org::new(Some(path)).unwrap();
+        assert_eq!(as2org.as_count(), 4);
+        assert!(as2org.are_siblings(15169, 36040));
+        assert_eq!(as2org.dataset_date(), NaiveDate::from_ymd_opt(2024, 7, 1));
+    }
+
+    #[test]
+    #[cfg(feature = "oneio")]
+    fn test_load_cached() {
+        let dir = TempDir::new("ttl");
+        let cache_dir = dir.path();
+        let compressed = zstd::encode_all(TEST_DATA.as_bytes(), 3).unwrap();
+        dir.write("20240101.as-org2info.jsonl.zst", &compressed);
+        dir.write("20240701.as-org2info.jsonl.zst", &compressed);
+
+        // a fresh cache file is used without touching the network
+        let as2org =
+            As2org::load_cached(cache_dir, Duration::from_secs(3600), "http://127.0.0.1:1/")
+                .unwrap();
+        assert_eq!(as2org.dataset_date(), NaiveDate::from_ymd_opt(2024, 7, 1));
+
+        // a stale cache file is still used when fetching the latest dataset fails
+        let base_url = serve_once("<html></html>");
+        let as2org = As2org::load_cached(cache_dir, Duration::ZERO, base_url.as_str()).unwrap();
+        assert_eq!(as2org.dataset_date(), NaiveDate::from_ymd_opt(2024, 7, 1));
+
+        // without any cached file, the fetch error is returned
+        let dir = TempDir::new("ttl-empty");
+        let base_url = serve_once("<html></html>");
+        assert!(As2org::load_cached(dir.path(), Duration::ZERO, base_url.as_str()).is_err());
+    }
+
+    #[test]
+    fn test_clone_across_threads() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<As2org>();
+
+        let as2org = As2org::from_jsonl_str(TEST_DATA).unwrap();
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let as2org = as2org.clone();
+                std::thread::spawn(move || {
+                    assert!(Arc::ptr_eq(&as2org.as_map, &as2org.clone().as_map));
+                    as2org.get_siblings(15169).unwrap().len()
+                })
+            })
+            .collect();
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), 2);
+        }
+        assert_eq!(as2org.get_as_info(15169).unwrap().org_id, "GOGL-ARIN");
+    }
+
+    #[test]
+    fn test_sibling_count() {
+        let as2org = As2org::from_jsonl_str(TEST_DATA).unwrap();
+        assert_eq!(as2org.sibling_count(15169), Some(2));
+        assert_eq!(as2org.sibling_count(13335), Some(1));
+        assert_eq!(as2org.sibling_count(64512), None);
+
+        // orphan ASes are not counted, as with get_siblings
+        let as2org = As2org::from_jsonl_str(FIXTURE).unwrap();
+        for &asn in as2org.as_map.keys() {
+            let siblings = as2org.get_siblings(asn).map(|v| v.len());
+            assert_eq!(as2org.sibling_count(asn), siblings, "AS{asn}");
+        }
+        assert_eq!(as2org.sibling_count(64512), Some(0));
+    }
+
+    #[test]
+    fn test_orphan_asns() {
+        let as2org = As2org::from_jsonl_str(TEST_DATA).unwrap();
+        assert!(as2org.orphan_asns().is_empty());
+
+        let data = format!(
+            "{TEST_DATA}{}\n{}\n",
+            r#"{"changed":"20240701","asn":"64513","name":"DANGLING2","organizationId":"MISSING-ARIN","opaqueId":"","source":"ARIN","type":"ASN"}"#,
+            r#"{"changed":"20240701","asn":"64512","name":"DANGLING1","organizationId":"MISSING-ARIN","opaqueId":"","source":"ARIN","type":"ASN"}"#,
+        );
+        let as2org = As2org::from_jsonl_str(data.as_str()).unwrap();
+        assert_eq!(as2org.orphan_asns(), vec![64512, 64513]);
+        assert!(as2org.get_as_info(64512).is_none());
+    }
+
+    #[test]
+    fn test_get_as_info_partial() {
+        let data = format!(
+            "{TEST_DATA}{}\n",
+            r#"{"changed":"20240701","asn":"64512","name":"DANGLING","organizationId":"MISSING-RIPE","opaqueId":"","source":"RIPE","type":"ASN"}"#,
+        );
+        let as2org = As2org::from_jsonl_str(data.as_str()).unwrap();
+
+        let info = as2org.get_as_info_partial(64512).unwrap();
+        assert_eq!(info.name, "DANGLING");
+        assert_eq!(info.org_id, "MISSING-RIPE");
+        assert_eq!(info.source, "RIPE");
+        assert!(info.org_name.is_empty());
+        assert!(info.country_code.is_empty());
+        assert!(info.org_changed.is_none());
+
+        assert_eq!(info.data_type, "ASN");
+
+        let info = as2org.get_as_info_partial(15169).unwrap();
+        assert_eq!(info.org_name, "Google LLC");
+        assert_eq!(info.country_code, "US");
+        assert!(as2org.get_as_info_partial(64513).is_none());
+    }
+
+    #[test]
+    fn test_write_csv() {
+        let data = TEST_DATA.replace("Google LLC", "Google \\\"Alphabet\\\", LLC");
+        let as2org = As2org::from_jsonl_str(data.as_str()).unwrap();
+        let mut buf = Vec::new();
+        as2org.write_csv(&mut buf).unwrap();
+        let csv = String::from_utf8(buf).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                "asn,name,country_code,org_id,org_name,source",
+                "3320,DTAG,DE,ORG-DTAG1-RIPE,Deutsche Telekom AG,RIPE",
+                "13335,CLOUDFLARENET,US,CLOUD14-ARIN,\"Cloudflare, Inc.\",ARIN",
+                "15169,GOOGLE,US,GOGL-ARIN,\"Google \"\"Alphabet\"\", LLC\",ARIN",
+                "36040,YOUTUBE,US,GOGL-ARIN,\"Google \"\"Alphabet\"\", LLC\",ARIN",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_to_enriched_json() {
+        let as2org = As2org::from_jsonl_str(TEST_DATA).unwrap();
+        let json = as2org.to_enriched_json().unwrap();
+        let infos: Vec<As2orgAsInfo> = serde_json::from_str(json.as_str()).unwrap();
+        let asns: Vec<u32> = infos.iter().map(|info| info.asn).collect();
+        assert_eq!(asns, vec![3320, 13335, 15169, 36040]);
+        assert_eq!(infos[2].org_name, "Google LLC");
+        assert_eq!(infos[2].data_type, "ASN");
+    }
+
+    #[test]
+    fn test_fuzzy_search_orgs() {
+        let chars = |s: &str| s.chars().collect::<Vec<char>>();
+        assert_eq!(levenshtein(&chars("kitten"), &chars("sitting")), 3);
+        assert_eq!(levenshtein(&chars(""), &chars("abc")), 3);
+        assert_eq!(levenshtein(&chars("level 3"), &chars("level3")), 1);
+
+        let as2org = As2org::from_jsonl_str(TEST_DATA).unwrap();
+        let res = as2org.fuzzy_search_orgs("googel llc", 2);
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].0.org_id, "GOGL-ARIN");
+        assert_eq!(res[0].1, 2);
+
+        let res = as2org.fuzzy_search_orgs("CLOUDFLARE INC", 3);
+        assert_eq!(res[0].0.org_id, "CLOUD14-ARIN");
+        assert_eq!(res[0].1, 2);
+        assert!(as2org.fuzzy_search_orgs("google", 1).is_empty());
+    }
+
+    #[test]
+    fn test_country_name() {
+        let as2org = As2org::from_jsonl_str(TEST_DATA).unwrap();
+        let mut info = as2org.get_as_info(3320).unwrap();
+        assert_eq!(info.country_name(), Some("Germany"));
+        info.country_code = "ZZ".to_string();
+        assert_eq!(info.country_name(), None);
+        info.country_code = String::new();
+        assert_eq!(info.country_name(), None);
+    }
+
+    #[test]
+    fn test_stats() {
+        let data = format!(
+            "{TEST_DATA}{}\n",
+            r#"{"changed":"20240701","asn":"64512","name":"DANGLING","organizationId":"MISSING-ARIN","opaqueId":"","source":"ARIN","type":"ASN"}"#,
+        );
+        let stats = As2org::from_jsonl_str(data.as_str()).unwrap().stats();
+        assert_eq!(stats.as_count, 5);
+        assert_eq!(stats.org_count, 3);
+        assert_eq!(stats.orphan_as_count, 1);
+        assert_eq!(stats.country_count, 2);
+        assert_eq!(
+            stats.as_count_by_source,
+            BTreeMap::from([("ARIN".to_string(), 3), ("RIPE".to_string(), 1)])
+        );
+    }
+
+    #[test]
+    fn test_org_size_histogram() {
+        let as2org = As2org::from_jsonl_str(TEST_DATA).unwrap();
+        assert_eq!(
+            as2org.org_size_histogram(),
+            BTreeMap::from([(1, 2), (2, 1)])
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "oneio")]
+    fn test_new_with_progress() {
+        let mut data = String::new();
+        for asn in 100_000..100_000 + PROGRESS_INTERVAL as u32 {
+            data.push_str(&format!(
+                r#"{{"changed":"20240701","asn":"{asn}","name":"AS{asn}","organizationId":"GOGL-ARIN","opaqueId":"","source":"ARIN","type":"ASN"}}"#
+            ));
+            data.push('\n');
+        }
+        data.push_str(TEST_DATA);
+        let dir = TempDir::new("progress");
+        let path = dir.write("20240701.as-org2info.jsonl", data);
+
+        let mut reported = vec![];
+        let as2org = As2org::new_with_progress(Some(path), |count| reported.push(count)).unwrap();
+        assert_eq!(reported, vec![PROGRESS_INTERVAL, PROGRESS_INTERVAL + 7]);
+        assert_eq!(as2org.sibling_count(15169), Some(PROGRESS_INTERVAL + 2));
+    }
+
+    #[test]
+    fn test_duplicate_asn_keeps_newer() {
+        let newer = r#"{"changed":"20240801","asn":"15169","name":"GOOGLE-NEW","organizationId":"GOGL-ARIN","opaqueId":"","source":"ARIN","type":"ASN"}"#;
+        let older = r#"{"changed":"20230101","asn":"15169","name":"GOOGLE-OLD","organizationId":"GOGL-ARIN","opaqueId":"","source":"ARIN","type":"ASN"}"#;
+        let undated = r#"{"asn":"15169","name":"GOOGLE-UNDATED","organizationId":"GOGL-ARIN","opaqueId":"","source":"ARIN","type":"ASN"}"#;
+
+        let as2org = As2org::from_jsonl_str(&format!("{TEST_DATA}{newer}\n{older}\n")).unwrap();
+        assert_eq!(as2org.get_as_info(15169).unwrap().name, "GOOGLE-NEW");
+
+        let as2org = As2org::from_jsonl_str(&format!("{TEST_DATA}{older}\n{newer}\n")).unwrap();
+        assert_eq!(as2org.get_as_info(15169).unwrap().name, "GOOGLE-NEW");
+
+        // without dates, the last record wins
+        let as2org = As2org::from_jsonl_str(&format!("{TEST_DATA}{newer}\n{undated}\n")).unwrap();
+        assert_eq!(as2org.get_as_info(15169).unwrap().name, "GOOGLE-UNDATED");
+    }
+
+    #[test]
+    #[cfg(feature = "oneio")]
+    fn test_new_with_report() {
+        let lines: Vec<&str> = TEST_DATA.lines().collect();
+        let data = format!("{TEST_DATA}{}\nnot json\n{}\n", lines[1], lines[0]);
+        let dir = TempDir::new("report");
+        let path = dir.write("20240701.as-org2info.jsonl", data);
+
+        let (as2org, report) = As2org::new_with_report(Some(path)).unwrap();
+        assert_eq!(report.duplicate_asns, vec![15169]);
+        assert_eq!(report.duplicate_org_ids, vec!["GOGL-ARIN".to_string()]);
+        assert_eq!(report.skipped_lines.len(), 1);
+        assert_eq!(report.skipped_lines[0].line, lines.len() + 2);
+        assert_eq!(as2org.as_count(), 4);
+    }
+
+    #[test]
+    fn test_from_gz_bytes() {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(TEST_DATA.as_bytes()).unwrap();
+        let bytes = encoder.finish().unwrap();
+
+        let as2org = As2org::from_gz_bytes(&bytes).unwrap();
+        assert_eq!(as2org.get_as_info(15169).unwrap().org_name, "Google LLC");
+        assert!(As2org::from_gz_bytes(TEST_DATA.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_get_sibling_group() {
+        let as2org = As2org::from_jsonl_str(TEST_DATA).unwrap();
+        let group = as2org.get_sibling_group(36040).unwrap();
+        assert_eq!(group.org.org_id, "GOGL-ARIN");
+        assert_eq!(group.org.name, "Google LLC");
+        let asns: Vec<u32> = group.members.iter().map(|info| info.asn).collect();
+        assert_eq!(asns, vec![15169, 36040]);
+        assert!(as2org.get_sibling_group(64512).is_none());
+    }
+
+    #[test]
+    fn test_iter_sibling_groups() {
+        let as2org = As2org::from_jsonl_str(TEST_DATA).unwrap();
+        let mut groups: Vec<(String, usize)> = as2org
+            .iter_sibling_groups()
+            .map(|group| (group.org.org_id, group.members.len()))
+            .collect();
+        groups.sort();
+        assert_eq!(
+            groups,
+            vec![
+                ("CLOUD14-ARIN".to_string(), 1),
+                ("GOGL-ARIN".to_string(), 2),
+                ("ORG-DTAG1-RIPE".to_string(), 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fix_latin1_misinterpretation() {
+        assert_eq!(fix_latin1_misinterpretation("OrangÃ©"), "Orangé");
+        assert_eq!(fix_latin1_misinterpretation("SocietÃ "), "Società");
+        assert_eq!(fix_latin1_misinterpretation("SocietÃ  SpA"), "Società SpA");
+        assert_eq!(fix_latin1_misinterpretation("Itâ€™s"), "It’s");
+        assert_eq!(fix_latin1_misinterpretation("Ã‚Â©"), "©");
+        assert_eq!(fix_latin1_misinterpretation("Ã¢â‚¬â„¢"), "’");
+        assert_eq!(
+            fix_latin1_misinterpretation("TelefÃ³nica de EspaÃ±a"),
+            "Telefónica de España"
+        );
+
+        // correctly encoded names are left unchanged
+        for name in [
+            "Google LLC",
+            "Société Générale",
+            "SÃO PAULO",
+            "IRMÃ ",
+            "東京大学",
+        ] {
+            assert!(matches!(
+                fix_latin1_misinterpretation(name),
+                Cow::Borrowed(n) if n == name
+            ));
+        }
+
+        let line = r#"{"changed":"20240701","name":"OrangÃ© S.A.","country":"FR","organizationId":"ORG-FT2-RIPE","source":"RIPE","type":"Organization"}"#;
+        let as2org = As2org::from_jsonl_str(line).unwrap();
+        assert_eq!(
+            as2org.get_org_info("ORG-FT2-RIPE").unwrap().name,
+            "Orangé S.A."
+        );
+    }
+
+    #[test]
+    fn test_get_asns_by_org_name() {
+        let data = format!(
+            "{TEST_DATA}{}\n{}\n",
+            r#"{"changed":"20240701","name":"Google LLC","country":"IE","organizationId":"ORG-GL1-RIPE","source":"RIPE","type":"Organization"}"#,
+            r#"{"changed":"20240701","asn":"43515","name":"YOUTUBE-EU","organizationId":"ORG-GL1-RIPE","opaqueId":"","source":"RIPE","type":"ASN"}"#,
+        );
+        let as2org = As2org::from_jsonl_str(data.as_str()).unwrap();
+        let asns: Vec<u32> = as2org
+            .get_asns_by_org_name("google llc")
+            .iter()
+            .map(|info| info.asn)
+            .collect();
+        assert_eq!(asns, vec![15169, 36040, 43515]);
+        assert!(as2org.get_asns_by_org_name("Google").is_empty());
+    }
+
+    #[test]
+    fn test_orgs_sharing_name() {
+        let as2org = As2org::from_jsonl_str(TEST_DATA).unwrap();
+        assert!(as2org.orgs_sharing_name().is_empty());
+
+        let data = format!(
+            "{TEST_DATA}{}\n",
+            r#"{"changed":"20240701","name":"Google LLC","country":"IE","organizationId":"ORG-GL1-RIPE","source":"RIPE","type":"Organization"}"#,
+        );
+        let as2org = As2org::from_jsonl_str(data.as_str()).unwrap();
+        assert_eq!(
+            as2org.orgs_sharing_name(),
+            HashMap::from([(
+                "Google LLC".to_string(),
+                vec!["GOGL-ARIN".to_string(), "ORG-GL1-RIPE".to_string()]
+            )])
+        );
+    }
+
+    #[test]
+    fn test_are_siblings_by_name() {
+        let data = format!(
+            "{TEST_DATA}{}\n{}\n",
+            r#"{"changed":"20240701","name":" google   LLC ","country":"IE","organizationId":"ORG-GL1-RIPE","source":"RIPE","type":"Organization"}"#,
+            r#"{"changed":"20240701","asn":"43515","name":"YOUTUBE-EU","organizationId":"ORG-GL1-RIPE","opaqueId":"","source":"RIPE","type":"ASN"}"#,
+        );
+        let as2org = As2org::from_jsonl_str(data.as_str()).unwrap();
+        assert!(as2org.are_siblings_by_name(15169, 43515));
+        assert!(!as2org.are_siblings(15169, 43515));
+        assert!(as2org.are_siblings_by_name(15169, 36040));
+        assert!(!as2org.are_siblings_by_name(15169, 13335));
+        assert!(!as2org.are_siblings_by_name(15169, 64512));
+    }
+
+    #[test]
+    fn test_get_latest_file_url() {
+        assert_eq!(
+            As2org::get_latest_file_url(),
+            "https://publicdata.caida.org/datasets/as-organizations/latest.as-org2info.jsonl.gz"
+        );
+        assert_eq!(
+            As2org::get_latest_file_url_with(".zst"),
+            "https://publicdata.caida.org/datasets/as-organizations/latest.as-org2info.jsonl.zst"
+        );
+        assert_eq!(
+            As2org::get_latest_file_url_with("zst"),
+            As2org::get_latest_file_url_with(".zst")
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "oneio")]
+    fn test_download_to() {
+        let dir = TempDir::new("download");
+        let dest = dir.file("mirror/20240701.as-org2info.jsonl.gz");
+        let url = format!("{}20240701.as-org2info.jsonl.gz", serve_once("raw bytes"));
+
+        As2org::download_to(url.as_str(), dest.as_str()).unwrap();
+        assert_eq!(std::fs::read(&dest).unwrap(), b"raw bytes");
+    }
+
+    #[test]
+    fn test_parse_error_line_number() {
+        let lines: Vec<&str> = TEST_DATA.lines().collect();
+        let data = format!("{}\n{{\"type\":\"ASN\",\n{}\n", lines[0], lines[1]);
+        let err = As2org::from_jsonl_str(data.as_str()).err().unwrap();
+        assert!(err.to_string().contains("line 2"), "{err}");
+    }
+
+    #[test]
+    fn test_countries() {
+        let data = format!(
+            "{TEST_DATA}{}\n{}\n",
+            r#"{"changed":"20240701","name":"Unknown","country":"","organizationId":"UNKNOWN-ARIN","source":"ARIN","type":"Organization"}"#,
+            r#"{"changed":"20240701","name":"Invalid","country":"ZZ","organizationId":"INVALID-ARIN","source":"ARIN","type":"Organization"}"#,
+        );
+        let as2org = As2org::from_jsonl_str(data.as_str()).unwrap();
+        assert_eq!(as2org.countries(), vec!["DE".to_string(), "US".to_string()]);
+    }
+
+    #[test]
+    fn test_sources() {
+        let as2org = As2org::from_jsonl_str(TEST_DATA).unwrap();
+        assert_eq!(
+            as2org.sources(),
+            vec!["ARIN".to_string(), "RIPE".to_string()]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "oneio")]
+    fn test_from_paths() {
+        let dir = TempDir::new("paths");
+        let lines: Vec<&str> = TEST_DATA.lines().collect();
+        let paths = vec![
+            dir.write("20240601.as-org2info.jsonl", lines[..5].join("\n")),
+            dir.write("20240701.as-org2info.jsonl", lines[5..].join("\n")),
+        ];
+        let as2org = As2org::from_paths(&paths).unwrap();
+        assert_eq!(as2org.get_as_info(15169).unwrap().org_id, "GOGL-ARIN");
+        assert_eq!(as2org.get_as_info(3320).unwrap().org_id, "ORG-DTAG1-RIPE");
+        assert_eq!(as2org.as_count(), 4);
+        assert_eq!(as2org.dataset_date(), NaiveDate::from_ymd_opt(2024, 7, 1));
+    }
+
+    #[test]
+    #[cfg(feature = "oneio")]
+    fn test_update_from() {
+        let data = TEST_DATA
+            .replace("Cloudflare, Inc.", "Cloudflare Inc.")
+            .replace(
+                r#""country":"US","organizationId":"CLOUD14-ARIN""#,
+                r#""country":"GB","organizationId":"CLOUD14-ARIN""#,
+            )
+            .replace(
+                r#""asn":"36040","name":"YOUTUBE","organizationId":"GOGL-ARIN""#,
+                r#""asn":"36040","name":"YOUTUBE","organizationId":"CLOUD14-ARIN""#,
+            )
+            .replace(r#""asn":"3320""#, r#""asn":"3321""#);
+        let dir = TempDir::new("update");
+        let path = dir.write("20240801.as-org2info.jsonl", data);
+
+        let mut as2org = As2org::from_jsonl_str(TEST_DATA).unwrap();
+        let previous = as2org.clone();
+        let summary = as2org.update_from(path.as_str()).unwrap();
+        assert_eq!(
+            summary,
+            UpdateSummary {
+                asns_added: 1,
+                asns_removed: 1,
+                asns_changed: 2,
+            }
+        );
+        assert!(as2org.contains_asn(3321));
+        assert!(!as2org.contains_asn(3320));
+        assert_eq!(as2org.dataset_date(), NaiveDate::from_ymd_opt(2024, 8, 1));
+        assert!(previous.contains_asn(3320));
+
+        // the updated indexes match those of a freshly loaded dataset
+        let loaded = As2org::new(Some(path)).unwrap();
+        assert_eq!(as2org.diff(&loaded), As2orgDiff::default());
+        for org_id in ["GOGL-ARIN", "CLOUD14-ARIN", "ORG-DTAG1-RIPE"] {
+            let mut asns = as2org.get_org_asns(org_id).unwrap();
+            asns.sort();
+            assert_eq!(Some(asns), loaded.get_org_asns(org_id), "{org_id}");
+        }
+        let gb: Vec<u32> = as2org
+            .get_asns_by_country("GB")
+            .iter()
+            .map(|a| a.asn)
+            .collect();
+        assert_eq!(gb, vec![13335, 36040]);
+        assert!(as2org
+            .get_asns_by_country("US")
+            .iter()
+            .all(|info| info.asn == 15169));
+
+        assert!(as2org.update_from("/nonexistent/file.jsonl").is_err());
+        assert!(as2org.contains_asn(3321));
+    }
+
+    #[test]
+    fn test_entries() {
+        let as2org = As2org::from_jsonl_str(TEST_DATA).unwrap();
+        let entries: Vec<As2orgEntry> = as2org.entries().collect();
+        assert_eq!(entries.len(), 7);
+        assert!(entries[..3]
+            .iter()
+            .all(|entry| matches!(entry, As2orgEntry::Org(_))));
+        let youtube = entries.iter().find_map(|entry| match entry {
+            As2orgEntry::As(as_entry) if as_entry.asn == 36040 => Some(as_entry),
+            _ => None,
+        });
+        assert_eq!(youtube.unwrap().changed.as_deref(), Some("20240701"));
+        assert_eq!(youtube.unwrap().org_id(), "GOGL-ARIN");
+        assert_eq!(youtube.unwrap().source(), "ARIN");
+    }
+
+    #[test]
+    fn test_lookup() {
+        let data = format!(
+            "{TEST_DATA}{}\n",
+            r#"{"changed":"20240701","asn":"64512","name":"DANGLING","organizationId":"MISSING-RIPE","opaqueId":"","source":"RIPE","type":"ASN"}"#,
+        );
+        let as2org = As2org::from_jsonl_str(data.as_str()).unwrap();
+
+        assert_eq!(as2org.lookup(15169).unwrap().org_name, "Google LLC");
+        assert_eq!(
+            as2org.lookup(64513).unwrap_err(),
+            LookupError::UnknownAsn(64513)
+        );
+        assert_eq!(
+            as2org.lookup(64512).unwrap_err(),
+            LookupError::MissingOrg {
+                asn: 64512,
+                org_id: "MISSING-RIPE".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_same_sibling_set() {
+        let as2org = As2org::from_jsonl_str(TEST_DATA).unwrap();
+        assert!(as2org.same_sibling_set(15169, 36040));
+        assert!(as2org.same_sibling_set(3320, 3320));
+        assert!(!as2org.same_sibling_set(15169, 13335));
+        assert!(!as2org.same_sibling_set(15169, 64512));
+    }
+
+    #[test]
+    #[cfg(feature = "oneio")]
+    fn test_parse_file_listing_same_date() {
+        let gz = r#"<a href="20240101.as-org2info.jsonl.gz">20240101.as-org2info.jsonl.gz</a>"#;
+        let zst = r#"<a href="20240101.as-org2info.jsonl.zst">20240101.as-org2info.jsonl.zst</a>"#;
+        let files = parse_file_listing(format!("{zst}\n{gz}").as_str(), BASE_URL);
+        assert_eq!(
+            files,
+            parse_file_listing(format!("{gz}\n{zst}").as_str(), BASE_URL)
+        );
+        assert_eq!(
+            files.last().unwrap().0,
+            format!("{BASE_URL}20240101.as-org2info.jsonl.zst")
+        );
+    }
+
+    #[test]
+    fn test_asdot_asn() {
+        assert_eq!(parse_asplain_or_asdot("1.10"), Some(65546));
+        assert_eq!(parse_asplain_or_asdot("15169"), Some(15169));
+        assert_eq!(parse_asplain_or_asdot("0.15169"), Some(15169));
+        assert_eq!(parse_asplain_or_asdot("65536.1"), None);
+        assert_eq!(parse_asplain_or_asdot("1.2.3"), None);
+
+        let data = TEST_DATA.replace(r#""asn":"36040""#, r#""asn":"1.10""#);
+        let as2org = As2org::from_jsonl_str(data.as_str()).unwrap();
+        assert!(as2org.are_siblings(15169, 65546));
+        assert_eq!(as2org.get_as_info(65546).unwrap().name, "YOUTUBE");
+    }
+
+    #[test]
+    fn test_get_as_info_ref() {
+        let as2org = As2org::from_jsonl_str(TEST_DATA).unwrap();
+        let info = as2org.get_as_info_ref(15169).unwrap();
+        assert_eq!(info.name, "GOOGLE");
+        assert_eq!(info.org_name, "Google LLC");
+        assert_eq!(info.country_code, "US");
+        assert_eq!(info.as_changed, NaiveDate::from_ymd_opt(2024, 7, 1));
+
+        let owned = As2orgAsInfo::from(info);
+        let expected = as2org.get_as_info(15169).unwrap();
+        assert_eq!(
+            serde_json::to_value(owned).unwrap(),
+            serde_json::to_value(expected).unwrap()
+        );
+        assert!(as2org.get_as_info_ref(64512).is_none());
+    }
+
+    #[test]
+    fn test_as_info_json_format() {
+        let as2org = As2org::from_jsonl_str(TEST_DATA).unwrap();
+        let info = as2org.get_as_info(15169).unwrap();
+
+        let json = serde_json::to_string(&info).unwrap();
+        assert_eq!(
+            json,
+            r#"{"asn":15169,"name":"GOOGLE","country_code":"US","org_id":"GOGL-ARIN","org_name":"Google LLC","source":"ARIN","data_type":"ASN","opaque_id":"","as_changed":"2024-07-01","org_changed":"2024-07-01"}"#
+        );
+        let parsed: As2orgAsInfo = serde_json::from_str(json.as_str()).unwrap();
+        assert_eq!(parsed, info);
+    }
+
+    #[test]
+    fn test_largest_org_by_asn_count() {
+        let as2org = As2org::from_jsonl_str(TEST_DATA).unwrap();
+        let org = as2org.largest_org_by_asn_count().unwrap();
+        assert_eq!(org.org_id, "GOGL-ARIN");
+
+        // CLOUD14-ARIN and ORG-DTAG1-RIPE both have one AS
+        let data = TEST_DATA
+            .lines()
+            .filter(|line| !line.contains("GOGL-ARIN"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let as2org = As2org::from_jsonl_str(data.as_str()).unwrap();
+        let org = as2org.largest_org_by_asn_count().unwrap();
+        assert_eq!(org.org_id, "CLOUD14-ARIN");
+
+        assert!(As2org::from_jsonl_str("")
+            .unwrap()
+            .largest_org_by_asn_count()
+            .is_none());
+    }
+
+    #[test]
+    fn test_asn_count_by_country() {
+        let data = TEST_DATA.replace(r#""country":"DE""#, r#""country":"""#);
+        let as2org = As2org::from_jsonl_str(data.as_str()).unwrap();
+        assert_eq!(
+            as2org.asn_count_by_country(),
+            BTreeMap::from([("US".to_string(), 3)])
+        );
+
+        let as2org = As2org::from_jsonl_str(TEST_DATA).unwrap();
+        let counts = as2org.asn_count_by_country();
+        assert_eq!(counts.get("DE"), Some(&1));
+        assert_eq!(counts.get("US"), Some(&3));
+    }
+
+    #[test]
+    fn test_inconsistent_records() {
+        let data = TEST_DATA
+            .replace(
+                r#""asn":"36040","name":"YOUTUBE","organizationId":"GOGL-ARIN","opaqueId":"","source":"ARIN""#,
+                r#""asn":"36040","name":"YOUTUBE","organizationId":"GOGL-ARIN","opaqueId":"","source":"RIPE""#,
+            )
+            .replace(r#""organizationId":"ORG-DTAG1-RIPE","opaqueId":"","source":"RIPE""#, r#""organizationId":"ORG-DTAG1-RIPE","opaqueId":"","source":"ripe""#);
+        let as2org = As2org::from_jsonl_str(data.as_str()).unwrap();
+        assert_eq!(as2org.inconsistent_records(), vec![36040]);
+        assert!(As2org::from_jsonl_str(TEST_DATA)
+            .unwrap()
+            .inconsistent_records()
+            .is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "oneio")]
+    fn test_new_filtered() {
+        let dir = TempDir::new("filtered");
+        let path = dir.write("20240701.as-org2info.jsonl", TEST_DATA);
+
+        let keep = HashSet::from([15169, 3320, 64512]);
+        let as2org = As2org::new_filtered(Some(path), &keep).unwrap();
+        assert_eq!(as2org.as_count(), 2);
+        assert_eq!(as2org.org_count(), 2);
+        assert_eq!(as2org.get_as_info(15169).unwrap().org_name, "Google LLC");
+        assert_eq!(as2org.get_as_info(3320).unwrap().country_code, "DE");
+        assert!(as2org.get_as_info(36040).is_none());
+        assert!(as2org.get_org_info("CLOUD14-ARIN").is_none());
+        assert_eq!(as2org.dataset_date(), NaiveDate::from_ymd_opt(2024, 7, 1));
+    }
+
+    #[test]
+    fn test_get_siblings_by_source() {
+        let data = TEST_DATA.replace(
+            r#""asn":"36040","name":"YOUTUBE","organizationId":"GOGL-ARIN","opaqueId":"","source":"ARIN""#,
+            r#""asn":"36040","name":"YOUTUBE","organizationId":"GOGL-ARIN","opaqueId":"","source":"RIPE""#,
+        );
+        let as2org = As2org::from_jsonl_str(data.as_str()).unwrap();
+        let asns = |source: &str| -> Vec<u32> {
+            let siblings = as2org.get_siblings_by_source(15169, source).unwrap();
+            siblings.iter().map(|info| info.asn).collect()
+        };
+        assert_eq!(asns("arin"), vec![15169]);
+        assert_eq!(asns("RIPE"), vec![36040]);
+        assert!(asns("APNIC").is_empty());
+        assert!(as2org.get_siblings_by_source(64512, "ARIN").is_none());
+    }
+
+    #[test]
+    fn test_as_info_display() {
+        let as2org = As2org::from_jsonl_str(TEST_DATA).unwrap();
+        assert_eq!(
+            as2org.get_as_info(15169).unwrap().to_string(),
+            "AS15169 GOOGLE (org: GOGL-ARIN / Google LLC, US, ARIN)"
+        );
+    }
+
+    #[test]
+    fn test_parse_asn() {
+        assert_eq!(parse_asn("15169"), Some(15169));
+        assert_eq!(parse_asn("AS15169"), Some(15169));
+        assert_eq!(parse_asn("as15169"), Some(15169));
+        assert_eq!(parse_asn("ASN15169"), Some(15169));
+        assert_eq!(parse_asn("  AS 15169\n"), Some(15169));
+        assert_eq!(parse_asn("AS1.10"), Some(65546));
+        assert_eq!(parse_asn("4294967295"), Some(u32::MAX));
+        assert_eq!(parse_asn("4294967296"), None);
+        assert_eq!(parse_asn("AS"), None);
+        assert_eq!(parse_asn("ASX15169"), None);
+        assert_eq!(parse_asn("-1"), None);
+        assert_eq!(parse_asn(""), None);
+    }
+
+    #[test]
+    fn test_group_by_org() {
+        let as2org = As2org::from_jsonl_str(TEST_DATA).unwrap();
+        let groups = as2org.group_by_org(&[3320, 36040, 36040, 64512, 15169, 13335]);
+        assert_eq!(
+            groups,
+            HashMap::from([
+                ("GOGL-ARIN".to_string(), vec![36040, 15169]),
+                ("CLOUD14-ARIN".to_string(), vec![13335]),
+                ("ORG-DTAG1-RIPE".to_string(), vec![3320]),
+            ])
+        );
+        assert!(as2org.group_by_org(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_get_org_with_size() {
+        let as2org = As2org::from_jsonl_str(FIXTURE).unwrap();
+        let (org, size) = as2org.get_org_with_size("GOGL-ARIN").unwrap();
+        assert_eq!(org.name, "Google LLC");
+        assert_eq!(size, 3);
+        assert_eq!(as2org.get_org_with_size("BL-3229-ARIN").unwrap().1, 1);
+        assert!(as2org.get_org_with_size("MISSING-RIPE").is_none());
+
+        let data = format!(
+            "{FIXTURE}{}\n",
+            r#"{"changed":"20240701","name":"Empty Org","country":"NL","organizationId":"ORG-EMPTY-RIPE","source":"RIPE","type":"Organization"}"#
+        );
+        let as2org = As2org::from_jsonl_str(data.as_str()).unwrap();
+        assert_eq!(as2org.get_org_with_size("ORG-EMPTY-RIPE").unwrap().1, 0);
+    }
+
+    #[test]
+    #[cfg(feature = "oneio")]
+    fn test_no_records() {
+        let dir = TempDir::new("no-records");
+        let path = dir.write("20240701.as-org2info.jsonl", "\n");
+        assert!(matches!(
+            As2org::new(Some(path.clone())),
+            Err(As2orgError::NoRecords(p)) if p == path
+        ));
+        assert!(matches!(
+            As2org::new_filtered(Some(path), &HashSet::from([13335])),
+            Err(As2orgError::NoRecords(_))
+        ));
+
+        // a different kind of dataset fails to parse, unless all of its lines are skipped
+        let path = dir.write(
+            "20240703.as-org2info.jsonl",
+            "{\"type\":\"Prefix\",\"prefix\":\"1.0.0.0/24\"}\n",
+        );
+        assert!(matches!(
+            As2org::new(Some(path.clone())),
+            Err(As2orgError::Parse { line: 1, .. })
+        ));
+        assert!(matches!(
+            As2org::new_lenient(Some(path)),
+            Err(As2orgError::NoRecords(_))
+        ));
+
+        // parse errors are reported with their line
+        let path = dir.write(
+            "20240702.as-org2info.jsonl",
+            format!("{TEST_DATA}not json\n"),
+        );
+        assert!(matches!(
+            As2org::new(Some(path)),
+            Err(As2orgError::Parse { line: 8, .. })
+        ));
+    }
+
+    #[test]
+    fn test_get_country() {
+        let data = FIXTURE.replace(r#""country":"FR""#, r#""country":" fr ""#);
+        let as2org = As2org::from_jsonl_str(data.as_str()).unwrap();
+        assert_eq!(as2org.get_country(3320).as_deref(), Some("DE"));
+        assert_eq!(as2org.get_country(5511).as_deref(), Some("FR"));
+        assert!(as2org.get_country(64512).is_none());
+        assert!(as2org.get_country(64513).is_none());
+    }
+
+    #[test]
+    fn test_get_org_name() {
+        let as2org = As2org::from_jsonl_str(FIXTURE).unwrap();
+        assert_eq!(as2org.get_org_name(36040).as_deref(), Some("Google LLC"));
+        assert_eq!(as2org.get_org_name(3215).as_deref(), Some("Orangé S.A."));
+        assert!(as2org.get_org_name(64512).is_none());
+        assert!(as2org.get_org_name(64513).is_none());
+    }
+
+    #[test]
+    fn test_json_array_format() {
+        let jsonl = As2org::from_jsonl_str(FIXTURE).unwrap();
+        let array = As2org::from_jsonl_str(FIXTURE_ARRAY).unwrap();
+        assert_eq!(array.as_count(), jsonl.as_count());
+        assert_eq!(array.org_count(), jsonl.org_count());
+        assert_eq!(
+            array.to_enriched_json().unwrap(),
+            jsonl.to_enriched_json().unwrap()
+        );
+        assert_eq!(array.get_org_name(3215).as_deref(), Some("Orangé S.A."));
+
+        let compact = format!("\n{}", FIXTURE_ARRAY.replace('\n', ""));
+        assert_eq!(
+            As2org::from_jsonl_str(compact.as_str()).unwrap().as_count(),
+            10
+        );
+
+        // errors are reported with the line in the whole file
+        let broken = compact.replacen("\"asn\": \"15169\"", "\"asn\": \"x\"", 1);
+        assert!(matches!(
+            As2org::from_jsonl_str(broken.as_str()),
+            Err(As2orgError::Parse { line: 2, .. })
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "oneio")]
+    fn test_load_json_array_file() {
+        let path = FIXTURE_PATH.replace(".jsonl", ".json");
+        let as2org = As2org::new(Some(path.clone())).unwrap();
+        assert_eq!(as2org.dataset_date(), NaiveDate::from_ymd_opt(2024, 7, 1));
+        assert!(as2org.are_siblings(15169, 396982));
+
+        let mut count = 0;
+        for_each_entry(path.as_str(), |_| count += 1).unwrap();
+        assert_eq!(count, 16);
+    }
+
+    #[test]
+    fn test_sibling_edges() {
+        let as2org = As2org::from_jsonl_str(FIXTURE).unwrap();
+        assert_eq!(
+            as2org.sibling_edges(36040),
+            Some(vec![(36040, 15169), (36040, 396982)])
+        );
+        assert_eq!(as2org.sibling_edges(13335), Some(vec![]));
+        assert!(as2org.sibling_edges(64513).is_none());
+    }
+
+    #[cfg(feature = "oneio")]
+    #[test]
+    fn test_decompress_reader() {
+        let mut bz = bzip2::write::BzEncoder::new(vec![], bzip2::Compression::default());
+        bz.write_all(TEST_DATA.as_bytes()).unwrap();
+        let mut xz = xz2::write::XzEncoder::new(vec![], 6);
+        xz.write_all(TEST_DATA.as_bytes()).unwrap();
+        let mut lz = lz4::EncoderBuilder::new().build(vec![]).unwrap();
+        lz.write_all(TEST_DATA.as_bytes()).unwrap();
+        let files = [
+            ("20240701.as-org2info.jsonl.bz2", bz.finish().unwrap()),
+            ("20240701.as-org2info.jsonl.xz", xz.finish().unwrap()),
+            ("20240701.as-org2info.jsonl.lz4", lz.finish().0),
+            ("20240701.as-org2info.jsonl", TEST_DATA.as_bytes().to_vec()),
+        ];
+
+        for (path, bytes) in files {
+            let reader = std::io::Cursor::new(bytes);
+            let (entries, _) = parse_as2org_reader(path, reader, ParseOptions::default()).unwrap();
+            assert_eq!(entries.len(), 7, "{path}");
+        }
+    }
+
+    #[test]
+    fn test_interned_record_fields() {
+        let as2org = As2org::from_jsonl_str(TEST_DATA).unwrap();
+        let google = &as2org.as_map[&15169];
+        let youtube = &as2org.as_map[&36040];
+        let org = &as2org.org_map["GOGL-ARIN"];
+        assert!(Arc::ptr_eq(&google.org_id, &youtube.org_id));
+        assert!(Arc::ptr_eq(&google.org_id, &org.org_id));
+        assert!(Arc::ptr_eq(&google.org_id, &as2org.as_to_org[&15169]));
+        assert!(Arc::ptr_eq(&google.source, &org.source));
+        assert!(Arc::ptr_eq(
+            &org.country,
+            &as2org.org_map["CLOUD14-ARIN"].country
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "oneio")]
+    fn test_download_to_cache_failure() {
+        let dir = TempDir::new("cache-failure");
+        let url = "http://127.0.0.1:1/20240701.as-org2info.jsonl.gz";
+        let res = download_to_cache(dir.path(), url, |_, tmp_path| {
+            std::fs::write(tmp_path, "partial")?;
+            Err(As2orgError::NoDatasets(url.to_string()))
+        });
+        assert!(res.is_err());
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
     }
 }